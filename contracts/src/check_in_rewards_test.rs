@@ -0,0 +1,96 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use token_rewards::{TokenRewards, TokenRewardsClient};
+
+struct Setup<'a> {
+    env: Env,
+    manager: EventManagerClient<'a>,
+    rewards: TokenRewardsClient<'a>,
+    admin: Address,
+    organizer: Address,
+    event_id: u64,
+}
+
+/// A free event on an EventManager that reports check-ins to TokenRewards;
+/// TokenRewards only accepts them once `link_recorder` is called
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let asset = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let manager = EventManagerClient::new(&env, &env.register(EventManager, ()));
+    manager.init(&admin, &asset);
+
+    let rewards = TokenRewardsClient::new(&env, &env.register(TokenRewards, ()));
+    let text = String::from_str(&env, "Kaizen");
+    rewards.init(&admin, &text, &text, &7, &1_000_000, &10_000_000);
+    manager.set_rewards_contract(&admin, &rewards.address);
+
+    let organizer = Address::generate(&env);
+    let event_id = manager.create_event(&organizer, &text, &text, &5_000, &text, &0, &10, &0, &None);
+    manager.set_max_per_wallet(&organizer, &event_id, &0);
+
+    Setup { env, manager, rewards, admin, organizer, event_id }
+}
+
+impl Setup<'_> {
+    fn link_recorder(&self) {
+        self.rewards.set_attendance_recorder(&self.admin, &self.manager.address);
+    }
+}
+
+#[test]
+fn test_check_in_records_attendance() {
+    let s = setup();
+    s.link_recorder();
+    let holder = Address::generate(&s.env);
+    let ticket_id = s.manager.purchase_ticket(&holder, &s.event_id, &0, &None, &None);
+
+    s.manager.check_in(&s.organizer, &ticket_id);
+    assert_eq!(s.rewards.get_attendance_count(&holder), 1);
+}
+
+#[test]
+fn test_check_in_without_attendance_recorder() {
+    let s = setup();
+    let holder = Address::generate(&s.env);
+    let ticket_id = s.manager.purchase_ticket(&holder, &s.event_id, &0, &None, &None);
+
+    s.manager.check_in(&s.organizer, &ticket_id);
+    assert!(s.manager.get_ticket(&ticket_id).checked_in);
+    assert_eq!(s.rewards.get_attendance_count(&holder), 0);
+}
+
+#[test]
+fn test_check_in_credits_referral() {
+    let s = setup();
+    s.link_recorder();
+    s.rewards.set_referral_reward(&s.admin, &10);
+    s.rewards.fund_referrals(&s.admin, &100);
+    let referrer = Address::generate(&s.env);
+    let holder = Address::generate(&s.env);
+    let ticket_id = s.manager.purchase_ticket(&holder, &s.event_id, &0, &None, &Some(referrer.clone()));
+
+    s.manager.check_in(&s.organizer, &ticket_id);
+    assert_eq!(s.rewards.get_referral_total(&referrer), 10);
+}
+
+#[test]
+fn test_check_in_while_rewards_paused() {
+    let s = setup();
+    s.link_recorder();
+    s.rewards.set_referral_reward(&s.admin, &10);
+    s.rewards.fund_referrals(&s.admin, &100);
+    s.rewards.set_pause_guardian(&s.admin, &s.admin);
+    s.rewards.pause(&s.admin, &token_rewards::PauseScope::All, &None);
+    let referrer = Address::generate(&s.env);
+    let holder = Address::generate(&s.env);
+    let ticket_id = s.manager.purchase_ticket(&holder, &s.event_id, &0, &None, &Some(referrer.clone()));
+
+    s.manager.check_in(&s.organizer, &ticket_id);
+    assert!(s.manager.get_ticket(&ticket_id).checked_in);
+    assert_eq!(s.rewards.get_attendance_count(&holder), 0);
+    assert_eq!(s.rewards.get_referral_total(&referrer), 0);
+}
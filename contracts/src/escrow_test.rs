@@ -0,0 +1,127 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::vec;
+
+struct Setup<'a> {
+    env: Env,
+    manager: EventManagerClient<'a>,
+    token: token::Client<'a>,
+    admin: Address,
+    organizer: Address,
+    event_id: u64,
+}
+
+/// An event at 5_000 with tickets of 100, a 5% platform fee, a dispute window
+/// of 1_000 and half of net sales released early
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let asset = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let manager = EventManagerClient::new(&env, &env.register(EventManager, ()));
+    manager.init(&admin, &asset);
+    manager.set_platform_config(&admin, &500, &Address::generate(&env), &1_000, &5_000);
+
+    let organizer = Address::generate(&env);
+    let text = String::from_str(&env, "Concert");
+    let event_id = manager.create_event(&organizer, &text, &text, &5_000, &text, &100, &10, &0, &None);
+    manager.set_max_per_wallet(&organizer, &event_id, &0);
+
+    let token = token::Client::new(&env, &asset);
+    Setup { env, manager, token, admin, organizer, event_id }
+}
+
+impl Setup<'_> {
+    fn buy(&self) -> (Address, u64) {
+        let buyer = Address::generate(&self.env);
+        token::StellarAssetClient::new(&self.env, &self.token.address).mint(&buyer, &100);
+        let ticket_id = self.manager.purchase_ticket(&buyer, &self.event_id, &0, &None, &None);
+        (buyer, ticket_id)
+    }
+}
+
+#[test]
+fn test_split_rounding_dust_goes_to_last_recipient() {
+    let s = setup();
+    let (first, second, third) = (
+        Address::generate(&s.env),
+        Address::generate(&s.env),
+        Address::generate(&s.env),
+    );
+    s.manager.set_payout_split(&s.organizer, &s.event_id, &vec![
+        &s.env,
+        PayoutShare { recipient: first.clone(), bps: 3_333 },
+        PayoutShare { recipient: second.clone(), bps: 3_333 },
+        PayoutShare { recipient: third.clone(), bps: 3_334 },
+    ]);
+    s.buy();
+
+    s.env.ledger().set_timestamp(6_000);
+    assert_eq!(s.manager.withdraw_proceeds(&s.organizer, &s.event_id), 95);
+    assert_eq!(s.token.balance(&first), 31);
+    assert_eq!(s.token.balance(&second), 31);
+    assert_eq!(s.token.balance(&third), 33);
+    assert_eq!(s.manager.get_escrow_balance(&s.event_id).amount, 0);
+    assert_eq!(s.manager.get_recipient_payout(&s.event_id, &third).amount, 33);
+
+    let fees = s.manager.get_event_fees(&s.event_id);
+    assert_eq!((fees.asset, fees.amount, fees.decimals), (s.token.address.clone(), 5, 7));
+    let accounting = s.manager.get_event_accounting(&s.event_id);
+    assert_eq!((accounting.gross, accounting.withdrawn), (100, 95));
+}
+
+#[test]
+fn test_early_release_before_dispute_window_ends() {
+    let s = setup();
+    s.buy();
+    s.buy();
+    s.buy();
+
+    // Half of the 285 net sales is available before the event
+    assert_eq!(s.manager.withdraw_proceeds(&s.organizer, &s.event_id), 142);
+    assert!(s.manager.try_withdraw_proceeds(&s.organizer, &s.event_id).is_err());
+
+    s.env.ledger().set_timestamp(5_999);
+    assert_eq!(s.manager.get_withdrawable(&s.event_id).amount, 0);
+
+    s.env.ledger().set_timestamp(6_000);
+    assert_eq!(s.manager.withdraw_proceeds(&s.organizer, &s.event_id), 143);
+    assert_eq!(s.token.balance(&s.organizer), 285);
+}
+
+#[test]
+fn test_refund_after_partial_withdrawal() {
+    let s = setup();
+    let (buyer, ticket_id) = s.buy();
+    s.buy();
+    s.buy();
+    assert_eq!(s.manager.withdraw_proceeds(&s.organizer, &s.event_id), 142);
+
+    // Net sales drop below what was already released early; nothing more unlocks
+    assert_eq!(s.manager.refund_ticket(&buyer, &ticket_id), 95);
+    assert_eq!(s.token.balance(&buyer), 95);
+    assert_eq!(s.manager.get_escrow_balance(&s.event_id).amount, 48);
+    assert_eq!(s.manager.get_withdrawable(&s.event_id).amount, 0);
+
+    s.env.ledger().set_timestamp(6_000);
+    assert_eq!(s.manager.withdraw_proceeds(&s.organizer, &s.event_id), 48);
+}
+
+#[test]
+fn test_admin_refunds_during_dispute_window() {
+    let s = setup();
+    let (buyer, ticket_id) = s.buy();
+    let (_, late_ticket_id) = s.buy();
+
+    // Past the event the holder can no longer cancel, but the admin may refund
+    s.env.ledger().set_timestamp(5_500);
+    assert!(s.manager.try_refund_ticket(&buyer, &ticket_id).is_err());
+    assert_eq!(s.manager.refund_ticket(&s.admin, &ticket_id), 95);
+    assert_eq!(s.token.balance(&buyer), 95);
+
+    s.env.ledger().set_timestamp(6_000);
+    assert!(s.manager.try_refund_ticket(&s.admin, &late_ticket_id).is_err());
+    assert_eq!(s.manager.withdraw_proceeds(&s.organizer, &s.event_id), 95);
+}
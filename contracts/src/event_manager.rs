@@ -1,5 +1,26 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, Map, IntoVal, TryFromVal, BytesN, log};
+// `create_event` takes every event field; Soroban has no builder for contract calls
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, String, Symbol, Vec, IntoVal, BytesN, Error, log};
+
+mod ownership;
+mod pausable;
+mod upgradeable;
+
+pub use pausable::PauseScope;
+
+#[cfg(test)]
+mod check_in_rewards_test;
+#[cfg(test)]
+mod escrow_test;
+#[cfg(test)]
+mod fiat_price_test;
+#[cfg(test)]
+mod reputation_test;
+#[cfg(test)]
+mod storage_migration_test;
+#[cfg(test)]
+mod ticket_deposit_test;
 
 #[derive(Clone)]
 #[contracttype]
@@ -11,20 +32,181 @@ pub struct Event {
     pub date: u64,
     pub location: String,
     pub price: i128,
+    pub payment_asset: Address, // SAC token `price` is denominated and paid in
     pub max_attendees: u32,
     pub current_attendees: u32,
     pub is_active: bool,
     pub nft_contract: Option<Address>,
     pub token_reward_amount: i128,
+    pub max_per_wallet: u32, // 0 = unlimited
+    pub ends_at: u64,
+    pub tickets_issued: u32, // lifetime count, including refunded tickets
+    pub sales_start: u64,    // 0 = on sale immediately
 }
 
 #[derive(Clone)]
 #[contracttype]
 pub struct Ticket {
     pub event_id: u64,
-    pub attendee: Address,
+    pub attendee: Address,  // ticket holder
+    pub purchaser: Address, // payer of the latest sale, differs from attendee for gifts
     pub purchase_timestamp: u64,
     pub ticket_id: u64,
+    pub checked_in: bool,
+    pub price_paid: i128,  // gross amount `purchaser` paid, in the payment token
+    pub fee_paid: i128,    // platform fee withheld from `price_paid`
+    pub points_paid: i128, // reward tokens redeemed towards the price (not refundable)
+    pub escrowed: i128,    // primary-sale proceeds held for this ticket; caps its refund
+    pub referrer: Option<Address>, // credited in TokenRewards when the ticket checks in
+}
+
+/// Ticket price set in a fiat currency and converted to the payment asset at
+/// purchase with a SEP-40 price oracle quoting that asset in the currency
+#[derive(Clone)]
+#[contracttype]
+pub struct FiatPrice {
+    pub oracle: Address,
+    pub currency: Symbol,   // informational, e.g. USD or INR; must be the oracle's base
+    pub amount: i128,       // price in minor units of `currency`
+    pub decimals: u32,      // decimals of `amount`, e.g. 2 for cents
+    pub max_staleness: u64, // seconds an oracle price stays usable
+}
+
+/// SEP-40 asset identifier
+#[derive(Clone)]
+#[contracttype]
+pub enum OracleAsset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// SEP-40 price record
+#[derive(Clone)]
+#[contracttype]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// An amount of a payment asset, with the asset's decimals for display
+#[derive(Clone)]
+#[contracttype]
+pub struct AssetAmount {
+    pub asset: Address,
+    pub amount: i128,
+    pub decimals: u32,
+}
+
+/// Share of event proceeds paid to one recipient, in basis points
+#[derive(Clone)]
+#[contracttype]
+pub struct PayoutShare {
+    pub recipient: Address,
+    pub bps: u32,
+}
+
+/// Contract-level platform fee settings
+#[derive(Clone)]
+#[contracttype]
+pub struct PlatformConfig {
+    pub fee_bps: u32,
+    pub treasury: Address,
+    pub dispute_window: u64,    // seconds after `ends_at` before proceeds unlock
+    pub early_release_bps: u32, // share of net sales withdrawable before then
+}
+
+/// Per-event ledger of ticket money held in escrow
+#[derive(Clone)]
+#[contracttype]
+pub struct EventAccounting {
+    pub gross: i128,
+    pub refunded: i128,
+    pub fees: i128,
+    pub withdrawn: i128,
+}
+
+/// An event's escrow totals in its payment asset, with the asset's decimals for display
+#[derive(Clone)]
+#[contracttype]
+pub struct AssetAccounting {
+    pub asset: Address,
+    pub decimals: u32,
+    pub gross: i128,
+    pub refunded: i128,
+    pub fees: i128,
+    pub withdrawn: i128,
+}
+
+/// Purchase perks for stakers at or above `min_level` in TokenRewards.
+/// The ticket holder must qualify, so perks are bound by the per-wallet limit.
+#[derive(Clone)]
+#[contracttype]
+pub struct PerkConfig {
+    pub min_level: u32,
+    pub early_access_secs: u64, // how long before `sales_start` perk holders may buy
+    pub reserved_seats: u32,    // seats only perk holders may take
+    pub discount_bps: u32,
+}
+
+/// Lets buyers redeem TokenRewards towards part of the ticket price.
+/// Redeemed reward tokens are burned or transferred to the organizer.
+#[derive(Clone)]
+#[contracttype]
+pub struct PointsConfig {
+    pub points_per_unit: i128, // reward-token units per unit of the payment token
+    pub max_share_bps: u32,    // cap on the share of the price payable with points
+    pub burn: bool,
+}
+
+/// Refundable deposit required with each ticket of a free event
+#[derive(Clone)]
+#[contracttype]
+pub struct DepositConfig {
+    pub token: Address,      // SAC token the deposit is paid in
+    pub amount: i128,
+    pub forfeit_to: Address, // receives no-show deposits: the organizer or a charity
+    pub auto_return: bool,   // return on check-in, else the holder claims it afterwards
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[contracttype]
+pub enum DepositStatus {
+    Held,
+    Returned,
+    Forfeited,
+}
+
+/// Deposit paid for one ticket, on the terms in force when it was issued
+#[derive(Clone)]
+#[contracttype]
+pub struct TicketDeposit {
+    pub token: Address,
+    pub amount: i128,
+    pub forfeit_to: Address,
+    pub auto_return: bool,
+    pub status: DepositStatus,
+}
+
+/// An address's ticket history, used for its reputation score. Gifted tickets
+/// only count towards attendance, no-shows and cancellations once accepted.
+#[derive(Clone)]
+#[contracttype]
+pub struct AttendeeStats {
+    pub tickets_acquired: u32, // bought, gifted or resold to this address
+    pub events_attended: u32,  // tickets checked in, except at events the holder staffs
+    pub no_shows: u32,         // tickets still held, unused, when the event ended
+    pub cancellations: u32,    // tickets the holder refunded
+}
+
+/// Staff roles an organizer can delegate for a single event.
+/// `Admin` implies every other role.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[contracttype]
+pub enum Role {
+    Admin,
+    Editor,  // update event details and settings
+    Scanner, // check in attendees
+    Finance, // withdraw proceeds
 }
 
 #[derive(Clone)]
@@ -32,23 +214,117 @@ pub struct Ticket {
 pub enum DataKey {
     EventCounter,
     Event(u64),
-    Ticket(u64, Address), // (event_id, attendee)
+    TicketCounter,
+    Ticket(u64),                     // ticket_id
+    EventTicket(u64, u32),           // (event_id, sequence) -> ticket_id, in issue order
     EventAttendees(u64),
     UserTickets(Address),
+    HolderTicketCount(u64, Address), // (event_id, holder)
+    EventRole(u64, Address, Role),   // (event_id, account, role)
+    PaymentToken,                    // default payment asset for new events
+    EventAccounting(u64),
+    PayoutSplit(u64),
+    RecipientPayout(u64, Address),   // (event_id, recipient)
+    PlatformConfig,
+    OrganizerFeeBps(Address),        // per-organizer fee override
+    AccruedFees(Address),            // per asset, fees not yet withdrawn by the treasury
+    RewardsContract,                 // TokenRewards contract notified of check-ins
+    EventPerks(u64),
+    PointsConfig(u64),
+    ReferredBy(Address),             // first referrer of an address
+    AttendeeStats(Address),
+    MinReputation(u64),
+    NoShowCursor(u64),               // tickets of an ended event checked for no-shows
+    DepositConfig(u64),
+    TicketDeposit(u64),              // ticket_id
+    GiftAccepted(u64),               // ticket_id of a gift its holder has accepted
+    FiatPrice(u64),
+}
+
+/// `Event` as stored by storage version 1, before per-event payment assets
+#[derive(Clone)]
+#[contracttype(export = false)]
+pub struct EventV1 {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub organizer: Address,
+    pub date: u64,
+    pub location: String,
+    pub price: i128,
+    pub max_attendees: u32,
+    pub current_attendees: u32,
+    pub is_active: bool,
+    pub nft_contract: Option<Address>,
+    pub token_reward_amount: i128,
+    pub max_per_wallet: u32,
+    pub ends_at: u64,
+    pub tickets_issued: u32,
+    pub sales_start: u64,
+}
+
+/// `Ticket` as stored by storage version 1, before referrals and per-ticket escrow
+#[derive(Clone)]
+#[contracttype(export = false)]
+pub struct TicketV1 {
+    pub event_id: u64,
+    pub attendee: Address,
+    pub purchaser: Address,
+    pub purchase_timestamp: u64,
+    pub ticket_id: u64,
+    pub checked_in: bool,
+    pub price_paid: i128,
+    pub fee_paid: i128,
+    pub points_paid: i128,
+}
+
+/// Storage keys of version 1 that changed shape since
+#[derive(Clone)]
+#[contracttype(export = false)]
+pub enum DataKeyV1 {
+    AccruedFees, // fees in the single payment token
 }
 
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Highest reputation score
+const MAX_REPUTATION: u32 = 100;
+
+/// How far up the referral chain to look for circular referrals
+const MAX_REFERRAL_DEPTH: u32 = 8;
+
+/// Storage layout version written by this code
+const STORAGE_VERSION: u32 = 2;
+
+/// Version 1 ticket IDs were `event_id * LEGACY_TICKET_STRIDE + sequence`
+const LEGACY_TICKET_STRIDE: u64 = 10_000;
+
 #[contract]
 pub struct EventManager;
 
+/// Buyer's choices for a ticket purchase
+struct PurchaseOptions {
+    points: i128,
+    max_price: Option<i128>,
+    referrer: Option<Address>,
+}
+
 #[contractimpl]
 impl EventManager {
     /// Initialize the contract
-    pub fn init(env: Env) {
+    /// `admin` manages platform fees; `payment_token` is the default SAC token ticket prices are paid in.
+    pub fn init(env: Env, admin: Address, payment_token: Address) {
+        // Admin must authorize; panics if already initialized
+        ownership::init(&env, &admin);
+        upgradeable::set_version(&env, STORAGE_VERSION);
+
         let counter: u64 = 0;
         env.storage().persistent().set(&DataKey::EventCounter, &counter);
+        env.storage().persistent().set(&DataKey::PaymentToken, &payment_token);
     }
 
     /// Create a new event
+    /// `payment_asset` is the SAC token `price` is in; `None` uses the contract default.
     pub fn create_event(
         env: Env,
         organizer: Address,
@@ -59,9 +335,14 @@ impl EventManager {
         price: i128,
         max_attendees: u32,
         token_reward_amount: i128,
+        payment_asset: Option<Address>,
     ) -> u64 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
         organizer.require_auth();
 
+        let payment_asset = payment_asset.unwrap_or_else(|| Self::get_payment_token(env.clone()));
+
         // Get and increment event counter
         let mut counter: u64 = env.storage()
             .persistent()
@@ -78,11 +359,16 @@ impl EventManager {
             date,
             location,
             price,
+            payment_asset,
             max_attendees,
             current_attendees: 0,
             is_active: true,
             nft_contract: None,
             token_reward_amount,
+            max_per_wallet: 1,
+            ends_at: date,
+            tickets_issued: 0,
+            sales_start: 0,
         };
 
         // Store the event
@@ -98,68 +384,41 @@ impl EventManager {
     }
 
     /// Purchase a ticket for an event
+    /// `points` is the most reward tokens to redeem towards the price (0 for none).
+    /// `max_price` caps what the buyer pays in the payment asset; required for fiat-priced events.
+    /// `referrer` is rewarded in TokenRewards once the ticket checks in.
     pub fn purchase_ticket(
         env: Env,
         attendee: Address,
         event_id: u64,
+        points: i128,
+        max_price: Option<i128>,
+        referrer: Option<Address>,
     ) -> u64 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Purchases);
         attendee.require_auth();
 
-        // Get event
-        let mut event: Event = env.storage()
-            .persistent()
-            .get(&DataKey::Event(event_id))
-            .expect("Event not found");
-
-        // Check if event is active and not full
-        if !event.is_active {
-            panic!("Event is not active");
-        }
-
-        if event.current_attendees >= event.max_attendees {
-            panic!("Event is full");
-        }
-
-        // Check if user already has a ticket
-        let ticket_key = DataKey::Ticket(event_id, attendee.clone());
-        if env.storage().persistent().has(&ticket_key) {
-            panic!("Already have a ticket for this event");
-        }
-
-        // Create ticket
-        let ticket_id = event_id * 10000 + (event.current_attendees as u64) + 1;
-        let ticket = Ticket {
-            event_id,
-            attendee: attendee.clone(),
-            purchase_timestamp: env.ledger().timestamp(),
-            ticket_id,
-        };
-
-        // Update event attendees
-        event.current_attendees += 1;
-        
-        // Get and update attendees list
-        let mut attendees: Vec<Address> = env.storage()
-            .persistent()
-            .get(&DataKey::EventAttendees(event_id))
-            .unwrap_or(Vec::new(&env));
-        attendees.push_back(attendee.clone());
-
-        // Update user's tickets list
-        let mut user_tickets: Vec<u64> = env.storage()
-            .persistent()
-            .get(&DataKey::UserTickets(attendee.clone()))
-            .unwrap_or(Vec::new(&env));
-        user_tickets.push_back(ticket_id);
+        let options = PurchaseOptions { points, max_price, referrer };
+        Self::issue_ticket(&env, &attendee, &attendee, event_id, options)
+    }
 
-        // Store updates
-        env.storage().persistent().set(&DataKey::Event(event_id), &event);
-        env.storage().persistent().set(&ticket_key, &ticket);
-        env.storage().persistent().set(&DataKey::EventAttendees(event_id), &attendees);
-        env.storage().persistent().set(&DataKey::UserTickets(attendee.clone()), &user_tickets);
+    /// Purchase a ticket on behalf of another holder (gift). It only counts towards
+    /// the holder's reputation once they call `accept_gift`.
+    pub fn gift_ticket(
+        env: Env,
+        purchaser: Address,
+        holder: Address,
+        event_id: u64,
+        points: i128,
+        max_price: Option<i128>,
+    ) -> u64 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Purchases);
+        purchaser.require_auth();
 
-        log!(&env, "Ticket purchased: {} for event: {}", ticket_id, event_id);
-        ticket_id
+        let options = PurchaseOptions { points, max_price, referrer: None };
+        Self::issue_ticket(&env, &purchaser, &holder, event_id, options)
     }
 
     /// Get event details
@@ -170,6 +429,11 @@ impl EventManager {
             .expect("Event not found")
     }
 
+    /// Get the organizer of an event
+    pub fn get_event_organizer(env: Env, event_id: u64) -> Address {
+        Self::get_event(env, event_id).organizer
+    }
+
     /// Get user's tickets
     pub fn get_user_tickets(env: Env, user: Address) -> Vec<u64> {
         env.storage()
@@ -186,23 +450,23 @@ impl EventManager {
             .unwrap_or(Vec::new(&env))
     }
 
-    /// Update event status (organizer only)
+    /// Update event status (organizer or Editor)
     pub fn update_event_status(
         env: Env,
-        organizer: Address,
+        operator: Address,
         event_id: u64,
         is_active: bool,
     ) {
-        organizer.require_auth();
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
 
         let mut event: Event = env.storage()
             .persistent()
             .get(&DataKey::Event(event_id))
             .expect("Event not found");
 
-        if event.organizer != organizer {
-            panic!("Only organizer can update event status");
-        }
+        Self::require_role(&env, &event, &operator, Role::Editor);
 
         event.is_active = is_active;
         env.storage().persistent().set(&DataKey::Event(event_id), &event);
@@ -210,23 +474,23 @@ impl EventManager {
         log!(&env, "Event status updated: {} -> {}", event_id, is_active);
     }
 
-    /// Set NFT contract for event rewards
+    /// Set NFT contract for event rewards (organizer or Editor)
     pub fn set_event_nft_contract(
         env: Env,
-        organizer: Address,
+        operator: Address,
         event_id: u64,
         nft_contract: Address,
     ) {
-        organizer.require_auth();
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
 
         let mut event: Event = env.storage()
             .persistent()
             .get(&DataKey::Event(event_id))
             .expect("Event not found");
 
-        if event.organizer != organizer {
-            panic!("Only organizer can set NFT contract");
-        }
+        Self::require_role(&env, &event, &operator, Role::Editor);
 
         event.nft_contract = Some(nft_contract);
         env.storage().persistent().set(&DataKey::Event(event_id), &event);
@@ -244,7 +508,1648 @@ impl EventManager {
 
     /// Check if user has ticket for event
     pub fn has_ticket(env: Env, user: Address, event_id: u64) -> bool {
-        let ticket_key = DataKey::Ticket(event_id, user);
-        env.storage().persistent().has(&ticket_key)
+        Self::get_ticket_count(env, user, event_id) > 0
+    }
+
+    /// Get number of tickets a holder has for an event
+    pub fn get_ticket_count(env: Env, user: Address, event_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::HolderTicketCount(event_id, user))
+            .unwrap_or(0)
+    }
+
+    /// Get ticket details
+    pub fn get_ticket(env: Env, ticket_id: u64) -> Ticket {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Ticket(ticket_id))
+            .expect("Ticket not found")
+    }
+
+    /// Get the address that first referred `user`, if any
+    pub fn get_referrer(env: Env, user: Address) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::ReferredBy(user))
+    }
+
+    /// Set the maximum number of tickets a single wallet may hold (organizer or Editor)
+    pub fn set_max_per_wallet(
+        env: Env,
+        operator: Address,
+        event_id: u64,
+        max_per_wallet: u32,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let mut event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Editor);
+
+        event.max_per_wallet = max_per_wallet;
+        env.storage().persistent().set(&DataKey::Event(event_id), &event);
+
+        log!(&env, "Max tickets per wallet set for event: {} -> {}", event_id, max_per_wallet);
+    }
+
+    /// Check in a ticket at the door (organizer or Scanner)
+    pub fn check_in(env: Env, operator: Address, ticket_id: u64) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let mut ticket: Ticket = env.storage()
+            .persistent()
+            .get(&DataKey::Ticket(ticket_id))
+            .expect("Ticket not found");
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(ticket.event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Scanner);
+
+        if ticket.checked_in {
+            panic!("Ticket already checked in");
+        }
+
+        ticket.checked_in = true;
+        env.storage().persistent().set(&DataKey::Ticket(ticket_id), &ticket);
+        // Staff scanning themselves in at their own events earn no reputation
+        if Self::counts_for_reputation(&env, &ticket) && !Self::is_event_staff(&env, &event, &ticket.attendee) {
+            Self::update_stats(&env, &ticket.attendee, |stats| stats.events_attended += 1);
+        }
+
+        let deposit = Self::get_ticket_deposit(env.clone(), ticket_id);
+        if deposit.is_some_and(|deposit| deposit.auto_return && deposit.status == DepositStatus::Held) {
+            Self::settle_deposit(&env, ticket_id, &ticket.attendee, DepositStatus::Returned);
+        }
+
+        // Report verified attendance for loyalty tracking; staff do not earn loyalty
+        // at events they run. A paused or misconfigured rewards contract must not
+        // hold up the door, so failures are only logged.
+        if let Some(rewards) = env.storage().persistent().get::<_, Address>(&DataKey::RewardsContract) {
+            if !Self::is_event_staff(&env, &event, &ticket.attendee) {
+                let recorded = env.try_invoke_contract::<(), Error>(
+                    &rewards,
+                    &Symbol::new(&env, "record_attendance"),
+                    (ticket.attendee.clone(), ticket.event_id).into_val(&env),
+                );
+                if recorded.is_err() {
+                    log!(&env, "Attendance not recorded in rewards for ticket {}", ticket_id);
+                }
+            }
+
+            // Only the holder's first referrer is credited, and not when event staff
+            // bought, hold or referred the ticket
+            if let Some(referrer) = &ticket.referrer {
+                let first_referrer = Self::get_referrer(env.clone(), ticket.attendee.clone());
+                let staff_involved = [&ticket.attendee, &ticket.purchaser, referrer]
+                    .iter()
+                    .any(|account| Self::is_event_staff(&env, &event, account));
+
+                if first_referrer.as_ref() == Some(referrer) && !staff_involved {
+                    let credited = env.try_invoke_contract::<i128, Error>(
+                        &rewards,
+                        &Symbol::new(&env, "credit_referral"),
+                        (referrer.clone(), ticket.attendee.clone(), ticket.event_id).into_val(&env),
+                    );
+                    if credited.is_err() {
+                        log!(&env, "Referral not credited in rewards for ticket {}", ticket_id);
+                    }
+                }
+            }
+        }
+
+        log!(&env, "Ticket checked in: {} for event: {}", ticket_id, ticket.event_id);
+    }
+
+    /// Configure how event proceeds are split (organizer or Finance).
+    /// Shares must sum to 10,000 bps and are locked once sales start.
+    pub fn set_payout_split(
+        env: Env,
+        operator: Address,
+        event_id: u64,
+        shares: Vec<PayoutShare>,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Finance);
+
+        if event.tickets_issued > 0 {
+            panic!("Payout split is locked once sales start");
+        }
+
+        if shares.is_empty() {
+            panic!("Payout split must have at least one recipient");
+        }
+
+        let mut total_bps: u32 = 0;
+        for share in shares.iter() {
+            if share.bps == 0 || share.bps > BPS_DENOMINATOR {
+                panic!("Payout share must be between 1 and 10000 bps");
+            }
+            total_bps = total_bps
+                .checked_add(share.bps)
+                .expect("Payout shares must sum to 10000 bps");
+        }
+
+        if total_bps != BPS_DENOMINATOR {
+            panic!("Payout shares must sum to 10000 bps");
+        }
+
+        env.storage().persistent().set(&DataKey::PayoutSplit(event_id), &shares);
+
+        log!(&env, "Payout split set for event: {} ({} recipients)", event_id, shares.len());
+    }
+
+    /// Withdraw escrowed proceeds to the payout recipients (organizer or Finance).
+    /// Everything unlocks once `ends_at` plus the dispute window has passed; before
+    /// that only the platform's early-release share of net sales is available.
+    /// Without a split, everything goes to the organizer.
+    pub fn withdraw_proceeds(env: Env, operator: Address, event_id: u64) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+        operator.require_auth();
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Finance);
+
+        let mut accounting = Self::event_accounting(&env, event_id);
+        let amount = Self::withdrawable(&env, &event, &accounting);
+
+        if amount <= 0 {
+            panic!("No proceeds to withdraw");
+        }
+
+        let mut shares: Vec<PayoutShare> = Self::get_payout_split(env.clone(), event_id);
+        if shares.is_empty() {
+            shares.push_back(PayoutShare {
+                recipient: event.organizer.clone(),
+                bps: BPS_DENOMINATOR,
+            });
+        }
+
+        let token = token::Client::new(&env, &event.payment_asset);
+
+        // Record the withdrawal before paying out
+        accounting.withdrawn += amount;
+        env.storage().persistent().set(&DataKey::EventAccounting(event_id), &accounting);
+
+        // Rounding dust goes to the last recipient so the amounts add up exactly
+        let mut remaining = amount;
+        let last = shares.len() - 1;
+        for (i, share) in shares.iter().enumerate() {
+            let payout = if i as u32 == last {
+                remaining
+            } else {
+                amount * share.bps as i128 / BPS_DENOMINATOR as i128
+            };
+            remaining -= payout;
+
+            if payout == 0 {
+                continue;
+            }
+
+            token.transfer(&env.current_contract_address(), &share.recipient, &payout);
+
+            let payout_key = DataKey::RecipientPayout(event_id, share.recipient.clone());
+            let paid: i128 = env.storage().persistent().get(&payout_key).unwrap_or(0);
+            env.storage().persistent().set(&payout_key, &(paid + payout));
+
+            env.events().publish(
+                (Symbol::new(&env, "payout"), event_id),
+                (share.recipient, payout),
+            );
+        }
+
+        log!(&env, "Proceeds withdrawn for event: {} -> {}", event_id, amount);
+        amount
+    }
+
+    /// Get the payout split for an event (empty if none configured)
+    pub fn get_payout_split(env: Env, event_id: u64) -> Vec<PayoutShare> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PayoutSplit(event_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get escrowed proceeds not yet withdrawn for an event
+    pub fn get_escrow_balance(env: Env, event_id: u64) -> AssetAmount {
+        let event = Self::get_event(env.clone(), event_id);
+        let accounting = Self::event_accounting(&env, event_id);
+        let escrow = accounting.gross - accounting.refunded - accounting.fees - accounting.withdrawn;
+        Self::asset_amount(&env, event.payment_asset, escrow)
+    }
+
+    /// Get the gross, refunded, fee and withdrawn totals for an event
+    pub fn get_event_accounting(env: Env, event_id: u64) -> AssetAccounting {
+        let event = Self::get_event(env.clone(), event_id);
+        let accounting = Self::event_accounting(&env, event_id);
+        AssetAccounting {
+            decimals: token::Client::new(&env, &event.payment_asset).decimals(),
+            asset: event.payment_asset,
+            gross: accounting.gross,
+            refunded: accounting.refunded,
+            fees: accounting.fees,
+            withdrawn: accounting.withdrawn,
+        }
+    }
+
+    /// Get the amount `withdraw_proceeds` would release right now
+    pub fn get_withdrawable(env: Env, event_id: u64) -> AssetAmount {
+        let event = Self::get_event(env.clone(), event_id);
+        let accounting = Self::event_accounting(&env, event_id);
+        let amount = Self::withdrawable(&env, &event, &accounting);
+        Self::asset_amount(&env, event.payment_asset, amount)
+    }
+
+    /// Set when the event ends, which starts the dispute window (organizer or Editor)
+    pub fn set_event_end(env: Env, operator: Address, event_id: u64, ends_at: u64) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let mut event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Editor);
+
+        if ends_at < event.date {
+            panic!("Event cannot end before it starts");
+        }
+
+        event.ends_at = ends_at;
+        env.storage().persistent().set(&DataKey::Event(event_id), &event);
+
+        log!(&env, "Event end set: {} -> {}", event_id, ends_at);
+    }
+
+    /// Set when general ticket sales open (organizer or Editor)
+    pub fn set_sales_start(env: Env, operator: Address, event_id: u64, sales_start: u64) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let mut event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Editor);
+
+        event.sales_start = sales_start;
+        env.storage().persistent().set(&DataKey::Event(event_id), &event);
+
+        log!(&env, "Sales start set: {} -> {}", event_id, sales_start);
+    }
+
+    /// Configure perks for TokenRewards stakers, or clear them with `None` (organizer or Editor)
+    pub fn set_event_perks(
+        env: Env,
+        operator: Address,
+        event_id: u64,
+        perks: Option<PerkConfig>,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Editor);
+
+        let key = DataKey::EventPerks(event_id);
+        match perks {
+            Some(perks) => {
+                if perks.discount_bps > BPS_DENOMINATOR || perks.reserved_seats > event.max_attendees {
+                    panic!("Invalid perk configuration");
+                }
+                env.storage().persistent().set(&key, &perks);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        log!(&env, "Perks updated for event: {}", event_id);
+    }
+
+    /// Accept reward-token redemptions for an event, or stop with `None` (organizer or Finance)
+    pub fn set_points_config(
+        env: Env,
+        operator: Address,
+        event_id: u64,
+        config: Option<PointsConfig>,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Finance);
+
+        let key = DataKey::PointsConfig(event_id);
+        match config {
+            Some(config) => {
+                if config.points_per_unit <= 0 || config.max_share_bps > BPS_DENOMINATOR {
+                    panic!("Invalid points configuration");
+                }
+                env.storage().persistent().set(&key, &config);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        log!(&env, "Points redemption updated for event: {}", event_id);
+    }
+
+    /// Get the reward-token redemption settings for an event
+    pub fn get_points_config(env: Env, event_id: u64) -> Option<PointsConfig> {
+        env.storage().persistent().get(&DataKey::PointsConfig(event_id))
+    }
+
+    /// Require a refundable deposit with each ticket of a free event, or stop with
+    /// `None`; tickets already issued keep their terms (organizer or Finance)
+    pub fn set_deposit_config(
+        env: Env,
+        operator: Address,
+        event_id: u64,
+        config: Option<DepositConfig>,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Finance);
+
+        let key = DataKey::DepositConfig(event_id);
+        match config {
+            Some(config) => {
+                if event.price != 0 || env.storage().persistent().has(&DataKey::FiatPrice(event_id)) {
+                    panic!("Deposits are only for free events");
+                }
+                if config.amount <= 0 {
+                    panic!("Deposit amount must be positive");
+                }
+                env.storage().persistent().set(&key, &config);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        log!(&env, "Deposit settings updated for event: {}", event_id);
+    }
+
+    /// Get the deposit required with tickets of an event
+    pub fn get_deposit_config(env: Env, event_id: u64) -> Option<DepositConfig> {
+        env.storage().persistent().get(&DataKey::DepositConfig(event_id))
+    }
+
+    /// Get the deposit paid for a ticket, if any
+    pub fn get_ticket_deposit(env: Env, ticket_id: u64) -> Option<TicketDeposit> {
+        env.storage().persistent().get(&DataKey::TicketDeposit(ticket_id))
+    }
+
+    /// Claim back the deposit of a checked-in ticket that was not returned on check-in
+    pub fn claim_deposit(env: Env, holder: Address, ticket_id: u64) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+        holder.require_auth();
+
+        let ticket: Ticket = env.storage()
+            .persistent()
+            .get(&DataKey::Ticket(ticket_id))
+            .expect("Ticket not found");
+
+        if ticket.attendee != holder {
+            panic!("Only ticket holder can claim the deposit");
+        }
+
+        if !ticket.checked_in {
+            panic!("Ticket not checked in");
+        }
+
+        Self::settle_deposit(&env, ticket_id, &holder, DepositStatus::Returned)
+    }
+
+    /// Get the staking perks configured for an event
+    pub fn get_event_perks(env: Env, event_id: u64) -> Option<PerkConfig> {
+        env.storage().persistent().get(&DataKey::EventPerks(event_id))
+    }
+
+    /// Require holders to have at least `min_reputation` (0-100) to get a ticket,
+    /// or 0 to allow anyone (organizer or Editor)
+    pub fn set_min_reputation(env: Env, operator: Address, event_id: u64, min_reputation: u32) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Editor);
+
+        if min_reputation > MAX_REPUTATION {
+            panic!("Minimum reputation cannot exceed 100");
+        }
+
+        env.storage().persistent().set(&DataKey::MinReputation(event_id), &min_reputation);
+
+        log!(&env, "Minimum reputation set: {} -> {}", event_id, min_reputation);
+    }
+
+    /// Get the minimum reputation required to hold a ticket for an event
+    pub fn get_min_reputation(env: Env, event_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::MinReputation(event_id))
+            .unwrap_or(0)
+    }
+
+    /// Accept a ticket gifted to the holder, so it counts towards their reputation.
+    /// Only possible before the event starts.
+    pub fn accept_gift(env: Env, holder: Address, ticket_id: u64) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        holder.require_auth();
+
+        let ticket: Ticket = env.storage()
+            .persistent()
+            .get(&DataKey::Ticket(ticket_id))
+            .expect("Ticket not found");
+
+        if ticket.attendee != holder {
+            panic!("Only ticket holder can accept the gift");
+        }
+
+        if ticket.purchaser == holder {
+            panic!("Ticket is not a gift");
+        }
+
+        if ticket.checked_in {
+            panic!("Ticket already checked in");
+        }
+
+        // Accepting only the gifts one turns up for would hide every no-show
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(ticket.event_id))
+            .expect("Event not found");
+        if env.ledger().timestamp() >= event.date {
+            panic!("Gifts must be accepted before the event starts");
+        }
+
+        env.storage().persistent().set(&DataKey::GiftAccepted(ticket_id), &true);
+
+        log!(&env, "Gift accepted: ticket {} by {}", ticket_id, holder);
+    }
+
+    /// Count no-shows for up to `limit` more tickets of an ended event and forfeit
+    /// their deposits (anyone). Returns how many tickets are left to check.
+    pub fn record_no_shows(env: Env, event_id: u64, limit: u32) -> u32 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        if env.ledger().timestamp() < event.ends_at {
+            panic!("Event has not ended");
+        }
+
+        let cursor_key = DataKey::NoShowCursor(event_id);
+        let checked: u32 = env.storage().persistent().get(&cursor_key).unwrap_or(0);
+        let until = event.tickets_issued.min(checked.saturating_add(limit));
+
+        for sequence in (checked + 1)..=until {
+            let ticket_id: u64 = env.storage()
+                .persistent()
+                .get(&DataKey::EventTicket(event_id, sequence))
+                .expect("Ticket not found");
+            // Refunded tickets no longer exist
+            if let Some(ticket) = env.storage().persistent().get::<_, Ticket>(&DataKey::Ticket(ticket_id)) {
+                if !ticket.checked_in {
+                    if Self::counts_for_reputation(&env, &ticket) {
+                        Self::update_stats(&env, &ticket.attendee, |stats| stats.no_shows += 1);
+                    }
+
+                    if let Some(deposit) = Self::get_ticket_deposit(env.clone(), ticket_id) {
+                        Self::settle_deposit(&env, ticket_id, &deposit.forfeit_to, DepositStatus::Forfeited);
+                    }
+                }
+            }
+        }
+
+        env.storage().persistent().set(&cursor_key, &until);
+        event.tickets_issued - until
+    }
+
+    /// Get an address's ticket history
+    pub fn get_attendee_stats(env: Env, user: Address) -> AttendeeStats {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AttendeeStats(user))
+            .unwrap_or(AttendeeStats {
+                tickets_acquired: 0,
+                events_attended: 0,
+                no_shows: 0,
+                cancellations: 0,
+            })
+    }
+
+    /// Reputation score from 0 to 100. Attendances count against no-shows, with a
+    /// cancellation weighing half a no-show; addresses with no history score 50.
+    pub fn get_reputation(env: Env, user: Address) -> u32 {
+        let stats = Self::get_attendee_stats(env, user);
+        // In half-units, starting from one attendance and one no-show so a new
+        // address scores neutral rather than perfect
+        let good = 2 * (stats.events_attended as u64 + 1);
+        let bad = 2 * (stats.no_shows as u64 + 1) + stats.cancellations as u64;
+        (MAX_REPUTATION as u64 * good / (good + bad)) as u32
+    }
+
+    /// Refund a ticket from escrow to whoever paid for it last, up to what the ticket
+    /// holds in escrow; the platform fee is not refunded. The holder may cancel before
+    /// the event starts; the platform admin may refund until the dispute window closes,
+    /// and organizer or Finance any time while the funds are still in escrow.
+    pub fn refund_ticket(env: Env, operator: Address, ticket_id: u64) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+        operator.require_auth();
+
+        let ticket: Ticket = env.storage()
+            .persistent()
+            .get(&DataKey::Ticket(ticket_id))
+            .expect("Ticket not found");
+
+        let event_id = ticket.event_id;
+        let mut event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        if operator == ticket.attendee {
+            if env.ledger().timestamp() >= event.date {
+                panic!("Tickets can only be cancelled before the event starts");
+            }
+        } else if ownership::is_owner(&env, &operator) {
+            let dispute_window = env.storage()
+                .persistent()
+                .get::<_, PlatformConfig>(&DataKey::PlatformConfig)
+                .map(|config| config.dispute_window)
+                .unwrap_or(0);
+            if env.ledger().timestamp() >= event.ends_at.saturating_add(dispute_window) {
+                panic!("Dispute window has closed");
+            }
+        } else {
+            Self::require_role(&env, &event, &operator, Role::Finance);
+        }
+
+        if ticket.checked_in {
+            panic!("Ticket already checked in");
+        }
+
+        let refund = (ticket.price_paid - ticket.fee_paid).min(ticket.escrowed);
+        let mut accounting = Self::event_accounting(&env, event_id);
+        if refund > 0 {
+            let escrow = accounting.gross - accounting.refunded - accounting.fees - accounting.withdrawn;
+            if escrow < refund {
+                panic!("Insufficient escrow for refund");
+            }
+
+            accounting.refunded += refund;
+            env.storage().persistent().set(&DataKey::EventAccounting(event_id), &accounting);
+
+            token::Client::new(&env, &event.payment_asset)
+                .transfer(&env.current_contract_address(), &ticket.purchaser, &refund);
+        }
+
+        if operator == ticket.attendee && Self::counts_for_reputation(&env, &ticket) {
+            Self::update_stats(&env, &ticket.attendee, |stats| stats.cancellations += 1);
+        }
+
+        // A cancelled ticket is not a no-show
+        let deposit = Self::get_ticket_deposit(env.clone(), ticket_id);
+        if deposit.is_some_and(|deposit| deposit.status == DepositStatus::Held) {
+            Self::settle_deposit(&env, ticket_id, &ticket.attendee, DepositStatus::Returned);
+        }
+
+        // Release the seat and remove the ticket from its holder
+        event.current_attendees -= 1;
+        env.storage().persistent().set(&DataKey::Event(event_id), &event);
+        env.storage().persistent().remove(&DataKey::Ticket(ticket_id));
+        env.storage().persistent().remove(&DataKey::GiftAccepted(ticket_id));
+        Self::remove_from_holder(&env, event_id, &ticket.attendee, ticket_id);
+
+        log!(&env, "Ticket refunded: {} for event: {} -> {}", ticket_id, event_id, refund);
+        refund
+    }
+
+    /// Get total paid out to a recipient for an event
+    pub fn get_recipient_payout(env: Env, event_id: u64, recipient: Address) -> AssetAmount {
+        let event = Self::get_event(env.clone(), event_id);
+        let paid = env.storage()
+            .persistent()
+            .get(&DataKey::RecipientPayout(event_id, recipient))
+            .unwrap_or(0);
+        Self::asset_amount(&env, event.payment_asset, paid)
+    }
+
+    /// Resell a ticket to a new holder; the platform fee is deducted from the seller's proceeds.
+    /// The buyer takes over as the ticket's payer, so a later refund goes to them.
+    /// A held deposit goes back to the seller and the buyer posts one on the same terms.
+    pub fn resell_ticket(
+        env: Env,
+        seller: Address,
+        buyer: Address,
+        ticket_id: u64,
+        price: i128,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Transfers);
+        seller.require_auth();
+        buyer.require_auth();
+
+        if price < 0 {
+            panic!("Resale price must not be negative");
+        }
+
+        let mut ticket: Ticket = env.storage()
+            .persistent()
+            .get(&DataKey::Ticket(ticket_id))
+            .expect("Ticket not found");
+
+        if ticket.attendee != seller {
+            panic!("Only ticket holder can resell");
+        }
+
+        if ticket.checked_in {
+            panic!("Ticket already checked in");
+        }
+
+        let event_id = ticket.event_id;
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        if !event.is_active {
+            panic!("Event is not active");
+        }
+
+        Self::require_reputation(&env, event_id, &buyer);
+
+        let buyer_held: u32 = env.storage()
+            .persistent()
+            .get(&DataKey::HolderTicketCount(event_id, buyer.clone()))
+            .unwrap_or(0);
+        if event.max_per_wallet > 0 && buyer_held >= event.max_per_wallet {
+            panic!("Ticket limit per wallet reached");
+        }
+
+        // Settle payment: buyer pays in full, seller receives price minus fee
+        let mut fee: i128 = 0;
+        if price > 0 {
+            let token = token::Client::new(&env, &event.payment_asset);
+
+            token.transfer(&buyer, &env.current_contract_address(), &price);
+            fee = Self::collect_fee(&env, &event, price);
+            if price > fee {
+                token.transfer(&env.current_contract_address(), &seller, &(price - fee));
+            }
+        }
+
+        // The held deposit is the seller's to get back; the buyer posts their own
+        if let Some(deposit) = Self::get_ticket_deposit(env.clone(), ticket_id) {
+            if deposit.status == DepositStatus::Held {
+                Self::settle_deposit(&env, ticket_id, &seller, DepositStatus::Returned);
+                token::Client::new(&env, &deposit.token)
+                    .transfer(&buyer, &env.current_contract_address(), &deposit.amount);
+                env.storage().persistent().set(&DataKey::TicketDeposit(ticket_id), &deposit);
+            }
+        }
+
+        // Move the ticket between holders
+        Self::remove_from_holder(&env, event_id, &seller, ticket_id);
+        Self::add_to_holder(&env, event_id, &buyer, ticket_id);
+        Self::update_stats(&env, &buyer, |stats| stats.tickets_acquired += 1);
+
+        ticket.attendee = buyer.clone();
+        ticket.purchaser = buyer.clone();
+        ticket.price_paid = price;
+        ticket.fee_paid = fee;
+        ticket.points_paid = 0;
+        env.storage().persistent().set(&DataKey::Ticket(ticket_id), &ticket);
+        env.storage().persistent().remove(&DataKey::GiftAccepted(ticket_id));
+
+        log!(&env, "Ticket {} resold from {} to {} for {}", ticket_id, seller, buyer, price);
+    }
+
+    /// Set the platform fee, treasury and payout timing (platform admin only)
+    pub fn set_platform_config(
+        env: Env,
+        admin: Address,
+        fee_bps: u32,
+        treasury: Address,
+        dispute_window: u64,
+        early_release_bps: u32,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+        Self::require_platform_admin(&env, &admin);
+
+        if fee_bps > BPS_DENOMINATOR || early_release_bps > BPS_DENOMINATOR {
+            panic!("Basis points cannot exceed 10000");
+        }
+
+        let config = PlatformConfig {
+            fee_bps,
+            treasury,
+            dispute_window,
+            early_release_bps,
+        };
+        env.storage().persistent().set(&DataKey::PlatformConfig, &config);
+
+        log!(&env, "Platform fee set to {} bps", fee_bps);
+    }
+
+    /// Override the platform fee for an organizer, or clear it with `None` (platform admin only)
+    pub fn set_organizer_fee(
+        env: Env,
+        admin: Address,
+        organizer: Address,
+        fee_bps: Option<u32>,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+        Self::require_platform_admin(&env, &admin);
+
+        let key = DataKey::OrganizerFeeBps(organizer.clone());
+        match fee_bps {
+            Some(bps) => {
+                if bps > BPS_DENOMINATOR {
+                    panic!("Fee cannot exceed 10000 bps");
+                }
+                env.storage().persistent().set(&key, &bps);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        log!(&env, "Organizer fee override updated for {}", organizer);
+    }
+
+    /// Withdraw all platform fees accrued in one asset (treasury only)
+    pub fn withdraw_fees(env: Env, treasury: Address, asset: Address) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+        treasury.require_auth();
+
+        let config = Self::get_platform_config(env.clone());
+        if config.treasury != treasury {
+            panic!("Only treasury can withdraw fees");
+        }
+
+        let key = DataKey::AccruedFees(asset.clone());
+        let amount: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if amount <= 0 {
+            panic!("No fees to withdraw");
+        }
+
+        env.storage().persistent().set(&key, &0i128);
+
+        token::Client::new(&env, &asset)
+            .transfer(&env.current_contract_address(), &treasury, &amount);
+
+        log!(&env, "Platform fees withdrawn: {}", amount);
+        amount
+    }
+
+    /// Set the TokenRewards contract that check-ins are reported to (platform admin only)
+    pub fn set_rewards_contract(env: Env, admin: Address, rewards: Address) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+        Self::require_platform_admin(&env, &admin);
+
+        env.storage().persistent().set(&DataKey::RewardsContract, &rewards);
+
+        log!(&env, "Rewards contract set: {}", rewards);
+    }
+
+    /// Get the platform fee settings
+    pub fn get_platform_config(env: Env) -> PlatformConfig {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PlatformConfig)
+            .expect("Platform config not set")
+    }
+
+    /// Get the fee rate applied to an organizer's sales
+    pub fn get_organizer_fee(env: Env, organizer: Address) -> u32 {
+        Self::fee_bps_for(&env, &organizer)
+    }
+
+    /// Get fees accrued in an asset and not yet withdrawn by the treasury
+    pub fn get_accrued_fees(env: Env, asset: Address) -> AssetAmount {
+        let amount = env.storage()
+            .persistent()
+            .get(&DataKey::AccruedFees(asset.clone()))
+            .unwrap_or(0);
+        Self::asset_amount(&env, asset, amount)
+    }
+
+    /// Get an event's ticket price with its payment asset
+    pub fn get_event_price(env: Env, event_id: u64) -> AssetAmount {
+        let event = Self::get_event(env.clone(), event_id);
+        Self::asset_amount(&env, event.payment_asset, event.price)
+    }
+
+    /// Price an event in fiat via a SEP-40 oracle, or go back to the fixed `price`
+    /// with `None` (organizer or Finance)
+    pub fn set_fiat_price(
+        env: Env,
+        operator: Address,
+        event_id: u64,
+        fiat_price: Option<FiatPrice>,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        operator.require_auth();
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        Self::require_role(&env, &event, &operator, Role::Finance);
+
+        let key = DataKey::FiatPrice(event_id);
+        match fiat_price {
+            Some(fiat_price) => {
+                if fiat_price.amount <= 0 || fiat_price.max_staleness == 0 {
+                    panic!("Invalid fiat price");
+                }
+                if env.storage().persistent().has(&DataKey::DepositConfig(event_id)) {
+                    panic!("Deposits are only for free events");
+                }
+                env.storage().persistent().set(&key, &fiat_price);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        log!(&env, "Fiat price updated for event: {}", event_id);
+    }
+
+    /// Get an event's fiat price, if it has one
+    pub fn get_fiat_price(env: Env, event_id: u64) -> Option<FiatPrice> {
+        env.storage().persistent().get(&DataKey::FiatPrice(event_id))
+    }
+
+    /// Get what a ticket costs now in the payment asset, before perks and points,
+    /// converting fiat prices at the latest oracle rate
+    pub fn quote_price(env: Env, event_id: u64) -> AssetAmount {
+        let event = Self::get_event(env.clone(), event_id);
+        let price = Self::current_price(&env, &event);
+        Self::asset_amount(&env, event.payment_asset, price)
+    }
+
+    /// Get the default payment asset for new events
+    pub fn get_payment_token(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PaymentToken)
+            .expect("Payment token not set")
+    }
+
+    /// Get total platform fees collected on primary sales for an event
+    pub fn get_event_fees(env: Env, event_id: u64) -> AssetAmount {
+        let event = Self::get_event(env.clone(), event_id);
+        let fees = Self::event_accounting(&env, event_id).fees;
+        Self::asset_amount(&env, event.payment_asset, fees)
+    }
+
+    /// Get the platform admin
+    pub fn get_admin(env: Env) -> Address {
+        ownership::get_owner(&env).expect("Admin not set")
+    }
+
+    /// Nominate a new platform admin; takes effect once they accept (admin only)
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address) {
+        ownership::propose(&env, &admin, &new_admin);
+    }
+
+    /// Accept a pending platform admin nomination
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        ownership::accept(&env, &new_admin);
+    }
+
+    /// Permanently give up the platform admin role (admin only)
+    pub fn renounce_admin(env: Env, admin: Address) {
+        ownership::renounce(&env, &admin);
+    }
+
+    /// Get the platform admin nominated but not yet accepted, if any
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        ownership::get_pending_owner(&env)
+    }
+
+    /// Appoint the pause guardian (platform admin only)
+    pub fn set_pause_guardian(env: Env, admin: Address, guardian: Address) {
+        admin.require_auth();
+        Self::require_platform_admin(&env, &admin);
+
+        pausable::set_guardian(&env, &guardian);
+    }
+
+    /// Pause a scope of entry points, optionally until `expires_at` (guardian only)
+    pub fn pause(env: Env, guardian: Address, scope: PauseScope, expires_at: Option<u64>) {
+        pausable::pause(&env, &guardian, scope, expires_at);
+    }
+
+    /// Lift a pause (guardian only)
+    pub fn unpause(env: Env, guardian: Address, scope: PauseScope) {
+        pausable::unpause(&env, &guardian, scope);
+    }
+
+    /// Check whether a scope is currently paused
+    pub fn is_paused(env: Env, scope: PauseScope) -> bool {
+        pausable::is_paused(&env, scope)
+    }
+
+    /// Get the pause guardian, if any
+    pub fn get_pause_guardian(env: Env) -> Option<Address> {
+        pausable::get_guardian(&env)
+    }
+
+    /// Replace the contract code, keeping all storage (admin only)
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can upgrade the contract");
+        }
+
+        upgradeable::upgrade(&env, new_wasm_hash);
+    }
+
+    /// Migrate up to `limit` storage entries towards the current layout (admin only)
+    /// Returns the storage version afterwards; call again until it equals `STORAGE_VERSION`
+    pub fn migrate(env: Env, admin: Address, limit: u32) -> u32 {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can migrate storage");
+        }
+
+        upgradeable::migrate(&env, STORAGE_VERSION, limit, Self::migrate_step)
+    }
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgradeable::get_version(&env)
+    }
+
+    /// Grant a staff role for an event (organizer only)
+    pub fn grant_role(
+        env: Env,
+        organizer: Address,
+        event_id: u64,
+        account: Address,
+        role: Role,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        organizer.require_auth();
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        if event.organizer != organizer {
+            panic!("Only organizer can manage roles");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::EventRole(event_id, account.clone(), role), &true);
+
+        env.events().publish(
+            (Symbol::new(&env, "role_granted"), event_id),
+            (account, role),
+        );
+    }
+
+    /// Revoke a staff role for an event (organizer only)
+    pub fn revoke_role(
+        env: Env,
+        organizer: Address,
+        event_id: u64,
+        account: Address,
+        role: Role,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        organizer.require_auth();
+
+        let event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        if event.organizer != organizer {
+            panic!("Only organizer can manage roles");
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::EventRole(event_id, account.clone(), role));
+
+        env.events().publish(
+            (Symbol::new(&env, "role_revoked"), event_id),
+            (account, role),
+        );
+    }
+
+    /// Check if an account holds a role for an event
+    pub fn has_role(env: Env, event_id: u64, account: Address, role: Role) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::EventRole(event_id, account, role))
+    }
+}
+
+impl EventManager {
+    /// Migration step `from_version -> from_version + 1`; see `upgradeable::MigrationStep`
+    fn migrate_step(env: &Env, from_version: u32, cursor: u32, limit: u32) -> Option<u32> {
+        match from_version {
+            1 => Self::migrate_v1(env, cursor, limit),
+            _ => upgradeable::no_steps(env, from_version, cursor, limit),
+        }
+    }
+
+    /// Version 1 -> 2: per-event payment assets, per-asset fees, global ticket IDs
+    /// with a per-event index, and ticket referrers and escrow.
+    ///
+    /// Walks the version 1 ticket ID space: `cursor` is the position
+    /// `event_id * LEGACY_TICKET_STRIDE + sequence`, where sequence 0 is the event
+    /// itself and 1..=`tickets_issued` its tickets. Cursor 0 moves the accrued fees
+    /// to the default payment asset and starts new ticket IDs above the legacy ones.
+    fn migrate_v1(env: &Env, cursor: u32, limit: u32) -> Option<u32> {
+        let storage = env.storage().persistent();
+        let event_count: u64 = storage.get(&DataKey::EventCounter).unwrap_or(0);
+        let payment_token: Address = storage
+            .get(&DataKey::PaymentToken)
+            .expect("Payment token not set");
+
+        let mut position = cursor as u64;
+        if position == 0 {
+            if let Some(fees) = storage.get::<_, i128>(&DataKeyV1::AccruedFees) {
+                storage.set(&DataKey::AccruedFees(payment_token.clone()), &fees);
+                storage.remove(&DataKeyV1::AccruedFees);
+            }
+            let first_ticket_id = (event_count + 1) * LEGACY_TICKET_STRIDE;
+            storage.set(&DataKey::TicketCounter, &first_ticket_id);
+            position = LEGACY_TICKET_STRIDE;
+        }
+
+        let mut tickets_issued: Option<u32> = None;
+        let mut migrated = 0;
+        while migrated < limit {
+            let event_id = position / LEGACY_TICKET_STRIDE;
+            let sequence = (position % LEGACY_TICKET_STRIDE) as u32;
+            if event_id > event_count {
+                return None;
+            }
+
+            if sequence == 0 {
+                let old: EventV1 = storage
+                    .get(&DataKey::Event(event_id))
+                    .expect("Event not found");
+                tickets_issued = Some(old.tickets_issued);
+                let event = Event {
+                    id: old.id,
+                    title: old.title,
+                    description: old.description,
+                    organizer: old.organizer,
+                    date: old.date,
+                    location: old.location,
+                    price: old.price,
+                    payment_asset: payment_token.clone(),
+                    max_attendees: old.max_attendees,
+                    current_attendees: old.current_attendees,
+                    is_active: old.is_active,
+                    nft_contract: old.nft_contract,
+                    token_reward_amount: old.token_reward_amount,
+                    max_per_wallet: old.max_per_wallet,
+                    ends_at: old.ends_at,
+                    tickets_issued: old.tickets_issued,
+                    sales_start: old.sales_start,
+                };
+                storage.set(&DataKey::Event(event_id), &event);
+            } else {
+                // Resuming mid-event, the event entry is already migrated
+                let issued = *tickets_issued.get_or_insert_with(|| {
+                    storage
+                        .get::<_, Event>(&DataKey::Event(event_id))
+                        .expect("Event not found")
+                        .tickets_issued
+                });
+                if sequence > issued {
+                    tickets_issued = None;
+                    position = (event_id + 1) * LEGACY_TICKET_STRIDE;
+                    continue;
+                }
+
+                // Refunded tickets no longer exist but keep their place in the index
+                let ticket_id = position;
+                if let Some(old) = storage.get::<_, TicketV1>(&DataKey::Ticket(ticket_id)) {
+                    let ticket = Ticket {
+                        event_id: old.event_id,
+                        attendee: old.attendee,
+                        purchaser: old.purchaser,
+                        purchase_timestamp: old.purchase_timestamp,
+                        ticket_id: old.ticket_id,
+                        checked_in: old.checked_in,
+                        price_paid: old.price_paid,
+                        fee_paid: old.fee_paid,
+                        points_paid: old.points_paid,
+                        escrowed: old.price_paid - old.fee_paid,
+                        referrer: None,
+                    };
+                    storage.set(&DataKey::Ticket(ticket_id), &ticket);
+                }
+                storage.set(&DataKey::EventTicket(event_id, sequence), &ticket_id);
+            }
+
+            position += 1;
+            migrated += 1;
+        }
+
+        Some(u32::try_from(position).expect("Migration cursor overflow"))
+    }
+
+    /// Panic unless `caller` is the organizer, an event Admin, or holds `role`
+    fn require_role(env: &Env, event: &Event, caller: &Address, role: Role) {
+        if *caller == event.organizer {
+            return;
+        }
+
+        let storage = env.storage().persistent();
+        if storage.has(&DataKey::EventRole(event.id, caller.clone(), Role::Admin))
+            || storage.has(&DataKey::EventRole(event.id, caller.clone(), role))
+        {
+            return;
+        }
+
+        panic!("Caller lacks required event role");
+    }
+
+    /// Whether `account` is the organizer or holds any staff role for the event
+    fn is_event_staff(env: &Env, event: &Event, account: &Address) -> bool {
+        *account == event.organizer
+            || [Role::Admin, Role::Editor, Role::Scanner, Role::Finance].iter().any(|role| {
+                env.storage()
+                    .persistent()
+                    .has(&DataKey::EventRole(event.id, account.clone(), *role))
+            })
+    }
+
+    /// Fee rate for an organizer: their override if set, else the platform rate
+    fn fee_bps_for(env: &Env, organizer: &Address) -> u32 {
+        if let Some(bps) = env.storage()
+            .persistent()
+            .get::<_, u32>(&DataKey::OrganizerFeeBps(organizer.clone()))
+        {
+            return bps;
+        }
+
+        env.storage()
+            .persistent()
+            .get::<_, PlatformConfig>(&DataKey::PlatformConfig)
+            .map(|config| config.fee_bps)
+            .unwrap_or(0)
+    }
+
+    /// Ticket price in the payment asset: the fixed `price`, or the fiat price
+    /// converted at the oracle's latest rate, rounded up
+    fn current_price(env: &Env, event: &Event) -> i128 {
+        let fiat: FiatPrice = match env.storage().persistent().get(&DataKey::FiatPrice(event.id)) {
+            Some(fiat) => fiat,
+            None => return event.price,
+        };
+
+        let quote: Option<PriceData> = env.invoke_contract(
+            &fiat.oracle,
+            &Symbol::new(env, "lastprice"),
+            (OracleAsset::Stellar(event.payment_asset.clone()),).into_val(env),
+        );
+        let quote = quote.expect("No oracle price for payment asset");
+
+        if quote.price <= 0 {
+            panic!("Invalid oracle price");
+        }
+        if env.ledger().timestamp() > quote.timestamp.saturating_add(fiat.max_staleness) {
+            panic!("Oracle price is stale");
+        }
+
+        let oracle_decimals: u32 = env.invoke_contract(&fiat.oracle, &Symbol::new(env, "decimals"), Vec::new(env));
+        let asset_decimals = token::Client::new(env, &event.payment_asset).decimals();
+
+        // amount / 10^fiat_decimals currency units, at quote.price / 10^oracle_decimals per whole token
+        let numerator = 10i128
+            .checked_pow(asset_decimals.saturating_add(oracle_decimals))
+            .and_then(|scale| fiat.amount.checked_mul(scale))
+            .expect("Price conversion overflow");
+        let denominator = 10i128
+            .checked_pow(fiat.decimals)
+            .and_then(|scale| quote.price.checked_mul(scale))
+            .expect("Price conversion overflow");
+        numerator.checked_add(denominator - 1).expect("Price conversion overflow") / denominator
+    }
+
+    fn asset_amount(env: &Env, asset: Address, amount: i128) -> AssetAmount {
+        let decimals = token::Client::new(env, &asset).decimals();
+        AssetAmount { asset, amount, decimals }
+    }
+
+    fn event_accounting(env: &Env, event_id: u64) -> EventAccounting {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EventAccounting(event_id))
+            .unwrap_or(EventAccounting {
+                gross: 0,
+                refunded: 0,
+                fees: 0,
+                withdrawn: 0,
+            })
+    }
+
+    /// Accrue the platform fee on `amount` for the treasury and return it
+    fn collect_fee(env: &Env, event: &Event, amount: i128) -> i128 {
+        let fee = amount * Self::fee_bps_for(env, &event.organizer) as i128 / BPS_DENOMINATOR as i128;
+        if fee == 0 {
+            return 0;
+        }
+
+        let key = DataKey::AccruedFees(event.payment_asset.clone());
+        let accrued: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(accrued + fee));
+
+        fee
+    }
+
+    /// Escrow an event may release now, given the dispute window and early-release share
+    fn withdrawable(env: &Env, event: &Event, accounting: &EventAccounting) -> i128 {
+        let escrow = accounting.gross - accounting.refunded - accounting.fees - accounting.withdrawn;
+        let config: Option<PlatformConfig> = env.storage().persistent().get(&DataKey::PlatformConfig);
+        let (dispute_window, early_release_bps) = match config {
+            Some(config) => (config.dispute_window, config.early_release_bps),
+            None => (0, 0),
+        };
+
+        if env.ledger().timestamp() >= event.ends_at.saturating_add(dispute_window) {
+            return escrow;
+        }
+
+        let net_sales = accounting.gross - accounting.refunded - accounting.fees;
+        let released = net_sales * early_release_bps as i128 / BPS_DENOMINATOR as i128;
+        (released - accounting.withdrawn).clamp(0, escrow)
+    }
+
+    /// Record that `holder` now holds `ticket_id`
+    fn add_to_holder(env: &Env, event_id: u64, holder: &Address, ticket_id: u64) {
+        let count_key = DataKey::HolderTicketCount(event_id, holder.clone());
+        let held: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        env.storage().persistent().set(&count_key, &(held + 1));
+
+        let mut user_tickets: Vec<u64> = env.storage()
+            .persistent()
+            .get(&DataKey::UserTickets(holder.clone()))
+            .unwrap_or(Vec::new(env));
+        user_tickets.push_back(ticket_id);
+        env.storage().persistent().set(&DataKey::UserTickets(holder.clone()), &user_tickets);
+
+        // Each holder is listed once per event
+        if held == 0 {
+            let mut attendees: Vec<Address> = env.storage()
+                .persistent()
+                .get(&DataKey::EventAttendees(event_id))
+                .unwrap_or(Vec::new(env));
+            attendees.push_back(holder.clone());
+            env.storage().persistent().set(&DataKey::EventAttendees(event_id), &attendees);
+        }
+    }
+
+    /// Record that `holder` no longer holds `ticket_id`
+    fn remove_from_holder(env: &Env, event_id: u64, holder: &Address, ticket_id: u64) {
+        let count_key = DataKey::HolderTicketCount(event_id, holder.clone());
+        let held: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        env.storage().persistent().set(&count_key, &(held - 1));
+
+        let user_tickets: Vec<u64> = env.storage()
+            .persistent()
+            .get(&DataKey::UserTickets(holder.clone()))
+            .unwrap_or(Vec::new(env));
+        let mut remaining_tickets: Vec<u64> = Vec::new(env);
+        for id in user_tickets.iter() {
+            if id != ticket_id {
+                remaining_tickets.push_back(id);
+            }
+        }
+        env.storage().persistent().set(&DataKey::UserTickets(holder.clone()), &remaining_tickets);
+
+        if held == 1 {
+            let attendees: Vec<Address> = env.storage()
+                .persistent()
+                .get(&DataKey::EventAttendees(event_id))
+                .unwrap_or(Vec::new(env));
+            let mut remaining_attendees: Vec<Address> = Vec::new(env);
+            for attendee in attendees.iter() {
+                if attendee != *holder {
+                    remaining_attendees.push_back(attendee);
+                }
+            }
+            env.storage().persistent().set(&DataKey::EventAttendees(event_id), &remaining_attendees);
+        }
+    }
+
+    /// Panic unless `admin` is the platform admin
+    fn require_platform_admin(env: &Env, admin: &Address) {
+        if !ownership::is_owner(env, admin) {
+            panic!("Only platform admin can change platform settings");
+        }
+    }
+
+    /// Staking perk level of `user` in the rewards contract (0 if none is set)
+    fn perk_level(env: &Env, user: &Address) -> u32 {
+        match env.storage().persistent().get::<_, Address>(&DataKey::RewardsContract) {
+            Some(rewards) => env.invoke_contract::<u32>(
+                &rewards,
+                &Symbol::new(env, "perk_level"),
+                (user.clone(),).into_val(env),
+            ),
+            None => 0,
+        }
+    }
+
+    /// Pay out a held ticket deposit to `to`, marking it `status`; returns the amount
+    fn settle_deposit(env: &Env, ticket_id: u64, to: &Address, status: DepositStatus) -> i128 {
+        let mut deposit = Self::get_ticket_deposit(env.clone(), ticket_id).expect("No deposit for this ticket");
+        if deposit.status != DepositStatus::Held {
+            panic!("Deposit already settled");
+        }
+
+        deposit.status = status;
+        env.storage().persistent().set(&DataKey::TicketDeposit(ticket_id), &deposit);
+        token::Client::new(env, &deposit.token)
+            .transfer(&env.current_contract_address(), to, &deposit.amount);
+
+        log!(env, "Deposit settled: ticket {} -> {}", ticket_id, deposit.amount);
+        deposit.amount
+    }
+
+    fn update_stats(env: &Env, user: &Address, update: impl FnOnce(&mut AttendeeStats)) {
+        let mut stats = Self::get_attendee_stats(env.clone(), user.clone());
+        update(&mut stats);
+        env.storage().persistent().set(&DataKey::AttendeeStats(user.clone()), &stats);
+    }
+
+    /// Whether a ticket's outcome counts towards its holder's reputation: tickets
+    /// the holder paid for, and gifts the holder has accepted
+    fn counts_for_reputation(env: &Env, ticket: &Ticket) -> bool {
+        ticket.purchaser == ticket.attendee
+            || env.storage().persistent().has(&DataKey::GiftAccepted(ticket.ticket_id))
+    }
+
+    fn require_reputation(env: &Env, event_id: u64, holder: &Address) {
+        let min_reputation = Self::get_min_reputation(env.clone(), event_id);
+        if min_reputation > 0 && Self::get_reputation(env.clone(), holder.clone()) < min_reputation {
+            panic!("Reputation below event minimum");
+        }
+    }
+
+    /// Panic on self-referral or if `holder` is already up `referrer`'s referral
+    /// chain; otherwise remember `referrer` as the holder's first referrer
+    fn record_referral(env: &Env, purchaser: &Address, holder: &Address, referrer: &Address) {
+        if referrer == holder || referrer == purchaser {
+            panic!("Cannot refer yourself");
+        }
+
+        let mut current = referrer.clone();
+        for _ in 0..MAX_REFERRAL_DEPTH {
+            match env.storage().persistent().get::<_, Address>(&DataKey::ReferredBy(current)) {
+                Some(upline) if upline == *holder => panic!("Circular referral"),
+                Some(upline) => current = upline,
+                None => break,
+            }
+        }
+
+        let key = DataKey::ReferredBy(holder.clone());
+        if !env.storage().persistent().has(&key) {
+            env.storage().persistent().set(&key, referrer);
+        }
+    }
+
+    /// Issue a ticket paid by `purchaser` and held by `holder`
+    fn issue_ticket(
+        env: &Env,
+        purchaser: &Address,
+        holder: &Address,
+        event_id: u64,
+        options: PurchaseOptions,
+    ) -> u64 {
+        let PurchaseOptions { points, max_price, referrer } = options;
+
+        // Get event
+        let mut event: Event = env.storage()
+            .persistent()
+            .get(&DataKey::Event(event_id))
+            .expect("Event not found");
+
+        // Check if event is active and not full
+        if !event.is_active {
+            panic!("Event is not active");
+        }
+
+        if event.current_attendees >= event.max_attendees {
+            panic!("Event is full");
+        }
+
+        // Staking perks: early access, reserved seats and discount
+        let perks: Option<PerkConfig> = env.storage().persistent().get(&DataKey::EventPerks(event_id));
+        let has_perks = match &perks {
+            Some(perks) => Self::perk_level(env, holder) >= perks.min_level,
+            None => false,
+        };
+
+        let now = env.ledger().timestamp();
+        let opens_at = match &perks {
+            Some(perks) if has_perks => event.sales_start.saturating_sub(perks.early_access_secs),
+            _ => event.sales_start,
+        };
+        if now < opens_at {
+            panic!("Ticket sales have not started");
+        }
+
+        let mut price = Self::current_price(env, &event);
+        if let Some(perks) = &perks {
+            if has_perks {
+                price -= price * perks.discount_bps as i128 / BPS_DENOMINATOR as i128;
+            } else if event.current_attendees + perks.reserved_seats >= event.max_attendees {
+                panic!("Remaining seats are reserved for perk holders");
+            }
+        }
+
+        Self::require_reputation(env, event_id, holder);
+
+        // Check the holder's ticket limit
+        let held: u32 = env.storage()
+            .persistent()
+            .get(&DataKey::HolderTicketCount(event_id, holder.clone()))
+            .unwrap_or(0);
+        if event.max_per_wallet > 0 && held >= event.max_per_wallet {
+            panic!("Ticket limit per wallet reached");
+        }
+
+        // Redeem reward tokens for part of the price
+        let mut points_paid: i128 = 0;
+        if points > 0 {
+            let config: PointsConfig = env.storage()
+                .persistent()
+                .get(&DataKey::PointsConfig(event_id))
+                .expect("Event does not accept points");
+            let rewards: Address = env.storage()
+                .persistent()
+                .get(&DataKey::RewardsContract)
+                .expect("Rewards contract not set");
+
+            let max_value = price * config.max_share_bps as i128 / BPS_DENOMINATOR as i128;
+            let value = (points / config.points_per_unit).min(max_value);
+            points_paid = value * config.points_per_unit;
+
+            if points_paid > 0 {
+                if config.burn {
+                    env.invoke_contract::<()>(
+                        &rewards,
+                        &Symbol::new(env, "burn"),
+                        (purchaser.clone(), points_paid).into_val(env),
+                    );
+                } else {
+                    env.invoke_contract::<()>(
+                        &rewards,
+                        &Symbol::new(env, "transfer"),
+                        (purchaser.clone(), event.organizer.clone(), points_paid).into_val(env),
+                    );
+                }
+                price -= value;
+            }
+        }
+
+        let fiat_priced = env.storage().persistent().has(&DataKey::FiatPrice(event_id));
+        match max_price {
+            Some(max_price) if price > max_price => panic!("Price exceeds buyer's maximum"),
+            None if fiat_priced => panic!("Fiat-priced events need a maximum price"),
+            _ => {}
+        }
+
+        // Collect payment into the event escrow
+        let mut fee: i128 = 0;
+        if price > 0 {
+            token::Client::new(env, &event.payment_asset)
+                .transfer(purchaser, &env.current_contract_address(), &price);
+
+            fee = Self::collect_fee(env, &event, price);
+
+            let mut accounting = Self::event_accounting(env, event_id);
+            accounting.gross += price;
+            accounting.fees += fee;
+            env.storage().persistent().set(&DataKey::EventAccounting(event_id), &accounting);
+        }
+
+        if let Some(referrer) = &referrer {
+            Self::record_referral(env, purchaser, holder, referrer);
+        }
+
+        // Create ticket
+        let ticket_id: u64 = env.storage()
+            .persistent()
+            .get(&DataKey::TicketCounter)
+            .unwrap_or(0)
+            + 1;
+        event.tickets_issued += 1;
+        env.storage().persistent().set(&DataKey::TicketCounter, &ticket_id);
+        env.storage().persistent().set(&DataKey::EventTicket(event_id, event.tickets_issued), &ticket_id);
+
+        // Hold the refundable deposit of a free event
+        if let Some(config) = Self::get_deposit_config(env.clone(), event_id) {
+            token::Client::new(env, &config.token)
+                .transfer(purchaser, &env.current_contract_address(), &config.amount);
+
+            let deposit = TicketDeposit {
+                token: config.token,
+                amount: config.amount,
+                forfeit_to: config.forfeit_to,
+                auto_return: config.auto_return,
+                status: DepositStatus::Held,
+            };
+            env.storage().persistent().set(&DataKey::TicketDeposit(ticket_id), &deposit);
+        }
+        let ticket = Ticket {
+            event_id,
+            attendee: holder.clone(),
+            purchaser: purchaser.clone(),
+            purchase_timestamp: env.ledger().timestamp(),
+            ticket_id,
+            checked_in: false,
+            price_paid: price,
+            fee_paid: fee,
+            points_paid,
+            escrowed: price - fee,
+            referrer,
+        };
+
+        // Update event attendees
+        event.current_attendees += 1;
+
+        // Store updates
+        env.storage().persistent().set(&DataKey::Event(event_id), &event);
+        env.storage().persistent().set(&DataKey::Ticket(ticket_id), &ticket);
+        Self::add_to_holder(env, event_id, holder, ticket_id);
+        Self::update_stats(env, holder, |stats| stats.tickets_acquired += 1);
+
+        log!(env, "Ticket purchased: {} for event: {}", ticket_id, event_id);
+        ticket_id
     }
 }
@@ -0,0 +1,118 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::symbol_short;
+
+/// SEP-40 oracle quoting every asset at one stored price, with 14 decimals
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, price: i128, timestamp: u64) {
+        env.storage().instance().set(&symbol_short!("price"), &PriceData { price, timestamp });
+    }
+
+    pub fn lastprice(env: Env, _asset: OracleAsset) -> Option<PriceData> {
+        env.storage().instance().get(&symbol_short!("price"))
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    manager: EventManagerClient<'a>,
+    oracle: MockOracleClient<'a>,
+    asset: Address,
+    organizer: Address,
+    event_id: u64,
+}
+
+/// An event priced at $10.00, paid in a 7-decimal asset quoted at $0.25
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let asset = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let manager = EventManagerClient::new(&env, &env.register(EventManager, ()));
+    manager.init(&admin, &asset);
+
+    let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+    oracle.set_price(&25_000_000_000_000, &990);
+
+    let organizer = Address::generate(&env);
+    let text = String::from_str(&env, "Meetup");
+    let event_id = manager.create_event(&organizer, &text, &text, &5_000, &text, &0, &10, &0, &None);
+    manager.set_fiat_price(&organizer, &event_id, &Some(FiatPrice {
+        oracle: oracle.address.clone(),
+        currency: symbol_short!("USD"),
+        amount: 1_000,
+        decimals: 2,
+        max_staleness: 60,
+    }));
+
+    Setup { env, manager, oracle, asset, organizer, event_id }
+}
+
+#[test]
+fn test_quote_converts_fiat_price() {
+    let s = setup();
+    assert_eq!(s.manager.quote_price(&s.event_id).amount, 400_000_000);
+
+    // $10.00 at $0.30 is 33.3333334 tokens, rounded up in the seller's favour
+    s.oracle.set_price(&30_000_000_000_000, &990);
+    assert_eq!(s.manager.quote_price(&s.event_id).amount, 333_333_334);
+}
+
+#[test]
+fn test_purchase_requires_max_price_covering_quote() {
+    let s = setup();
+    let buyer = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.asset).mint(&buyer, &1_000_000_000);
+
+    assert!(s.manager.try_purchase_ticket(&buyer, &s.event_id, &0, &None, &None).is_err());
+    assert!(s.manager.try_purchase_ticket(&buyer, &s.event_id, &0, &Some(399_999_999), &None).is_err());
+
+    let ticket_id = s.manager.purchase_ticket(&buyer, &s.event_id, &0, &Some(400_000_000), &None);
+    assert_eq!(s.manager.get_ticket(&ticket_id).price_paid, 400_000_000);
+    assert_eq!(token::Client::new(&s.env, &s.asset).balance(&buyer), 600_000_000);
+}
+
+#[test]
+fn test_stale_or_missing_price_is_rejected() {
+    let s = setup();
+    s.env.ledger().set_timestamp(1_050);
+    assert!(s.manager.try_quote_price(&s.event_id).is_ok());
+
+    s.env.ledger().set_timestamp(1_051);
+    assert!(s.manager.try_quote_price(&s.event_id).is_err());
+
+    s.oracle.set_price(&0, &1_051);
+    assert!(s.manager.try_quote_price(&s.event_id).is_err());
+}
+
+#[test]
+fn test_conversion_overflow_is_rejected() {
+    let s = setup();
+    s.manager.set_fiat_price(&s.organizer, &s.event_id, &Some(FiatPrice {
+        oracle: s.oracle.address.clone(),
+        currency: symbol_short!("USD"),
+        amount: i128::MAX / 1_000,
+        decimals: 2,
+        max_staleness: 60,
+    }));
+    assert!(s.manager.try_quote_price(&s.event_id).is_err());
+
+    s.manager.set_fiat_price(&s.organizer, &s.event_id, &Some(FiatPrice {
+        oracle: s.oracle.address.clone(),
+        currency: symbol_short!("USD"),
+        amount: 1_000,
+        decimals: 40,
+        max_staleness: 60,
+    }));
+    assert!(s.manager.try_quote_price(&s.event_id).is_err());
+}
@@ -0,0 +1,590 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, IntoVal, String, Symbol, Vec};
+
+mod ownership;
+mod pausable;
+mod upgradeable;
+
+pub use pausable::PauseScope;
+
+#[cfg(test)]
+mod governance_test;
+
+/// Voting rules, fixed at init
+#[derive(Clone)]
+#[contracttype]
+pub struct GovernanceConfig {
+    pub token: Address,           // TokenRewards contract providing checkpointed balances
+    pub voting_period: u64,       // seconds a proposal stays open
+    pub proposal_threshold: i128, // voting power needed to create a proposal
+    pub quorum_bps: u32,          // share of votable supply at the snapshot that must vote
+    pub threshold_bps: u32,       // share of cast votes the winning option needs
+}
+
+/// Whitelisted effect of a winning option, run through the TokenRewards contract.
+/// The governance contract must be TokenRewards' admin for `SetEventReward`, and
+/// pays `TreasuryTransfer`s from its own reward-token balance.
+#[derive(Clone)]
+#[contracttype]
+pub enum ProposalAction {
+    None,
+    SetEventReward(u64, i128, u64), // (event_id, reward_amount, claim_deadline)
+    TreasuryTransfer(Address, i128), // (to, amount)
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ProposalOption {
+    pub label: String,
+    pub action: ProposalAction,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub text_hash: BytesN<32>, // hash of the off-chain proposal text
+    pub options: Vec<ProposalOption>,
+    pub votes: Vec<i128>,      // tally per option
+    pub snapshot_ledger: u32,  // balances are read as of this ledger
+    pub start: u64,
+    pub end: u64,
+    pub executed: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[contracttype]
+pub enum ProposalStatus {
+    Active,
+    Defeated,
+    Succeeded,
+    Executed,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Config,
+    ProposalCounter,
+    Proposal(u64),
+    Vote(u64, Address),    // (proposal_id, voter) -> option
+    Counted(u64, Address), // (proposal_id, account) whose power has been used
+    Delegate(Address),     // delegator -> delegatee
+    Delegators(Address),   // delegatee -> delegators
+}
+
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Bounds the work of a single vote. Delegators without tokens give up their slot
+/// when the list is full, so empty accounts cannot crowd out real delegators.
+const MAX_DELEGATORS: u32 = 50;
+const MAX_OPTIONS: u32 = 10;
+
+/// Storage layout version written by this code
+const STORAGE_VERSION: u32 = 1;
+
+#[contract]
+pub struct Governance;
+
+#[contractimpl]
+impl Governance {
+    /// Initialize the governance contract
+    pub fn init(env: Env, admin: Address, config: GovernanceConfig) {
+        // Admin must authorize; panics if already initialized
+        ownership::init(&env, &admin);
+        upgradeable::set_version(&env, STORAGE_VERSION);
+
+        if config.voting_period == 0 {
+            panic!("Voting period must be positive");
+        }
+
+        if config.quorum_bps > BPS_DENOMINATOR || config.threshold_bps > BPS_DENOMINATOR {
+            panic!("Quorum and threshold cannot exceed 10000 bps");
+        }
+
+        env.storage().persistent().set(&DataKey::Config, &config);
+    }
+
+    /// Open a proposal; voting power is snapshotted at the previous ledger
+    pub fn propose(
+        env: Env,
+        proposer: Address,
+        text_hash: BytesN<32>,
+        options: Vec<ProposalOption>,
+    ) -> u64 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        proposer.require_auth();
+
+        if options.len() < 2 || options.len() > MAX_OPTIONS {
+            panic!("Proposal needs between 2 and 10 options");
+        }
+
+        let config = Self::get_config(env.clone());
+        let snapshot_ledger = env.ledger().sequence().saturating_sub(1);
+        if Self::balance_at(&env, &config, &proposer, snapshot_ledger) < config.proposal_threshold {
+            panic!("Voting power below proposal threshold");
+        }
+
+        let id: u64 = env.storage()
+            .persistent()
+            .get(&DataKey::ProposalCounter)
+            .unwrap_or(0)
+            + 1;
+
+        let mut votes = Vec::new(&env);
+        for _ in options.iter() {
+            votes.push_back(0i128);
+        }
+
+        let start = env.ledger().timestamp();
+        let proposal = Proposal {
+            id,
+            proposer: proposer.clone(),
+            text_hash,
+            options,
+            votes,
+            snapshot_ledger,
+            start,
+            end: start + config.voting_period,
+            executed: false,
+        };
+
+        env.storage().persistent().set(&DataKey::Proposal(id), &proposal);
+        env.storage().persistent().set(&DataKey::ProposalCounter, &id);
+
+        env.events().publish(
+            (Symbol::new(&env, "proposal_created"), id),
+            (proposer, proposal.end),
+        );
+        id
+    }
+
+    /// Vote for an option with the voter's own and delegated power.
+    /// Accounts that delegated cannot vote themselves.
+    pub fn vote(env: Env, voter: Address, proposal_id: u64, option: u32) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        voter.require_auth();
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id);
+        if env.ledger().timestamp() >= proposal.end {
+            panic!("Voting has ended");
+        }
+
+        if option >= proposal.options.len() {
+            panic!("Invalid option");
+        }
+
+        if Self::get_delegate(env.clone(), voter.clone()).is_some() {
+            panic!("Votes are delegated");
+        }
+
+        let vote_key = DataKey::Vote(proposal_id, voter.clone());
+        if env.storage().persistent().has(&vote_key) {
+            panic!("Already voted");
+        }
+
+        let power = Self::count_power(&env, &proposal, &voter);
+        if power == 0 {
+            panic!("No voting power");
+        }
+
+        proposal.votes.set(option, proposal.votes.get_unchecked(option) + power);
+        env.storage().persistent().set(&DataKey::Proposal(proposal_id), &proposal);
+        env.storage().persistent().set(&vote_key, &option);
+
+        env.events().publish(
+            (Symbol::new(&env, "vote_cast"), proposal_id),
+            (voter, option, power),
+        );
+        power
+    }
+
+    /// Run the winning option's action once voting has ended (anyone)
+    pub fn execute(env: Env, proposal_id: u64) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id);
+        if Self::proposal_status(env.clone(), proposal_id) != ProposalStatus::Succeeded {
+            panic!("Proposal has not succeeded");
+        }
+
+        proposal.executed = true;
+        env.storage().persistent().set(&DataKey::Proposal(proposal_id), &proposal);
+
+        let winner = Self::winning_option(&proposal).expect("No winning option");
+        let config = Self::get_config(env.clone());
+        let this = env.current_contract_address();
+
+        match proposal.options.get_unchecked(winner).action {
+            ProposalAction::None => {}
+            ProposalAction::SetEventReward(event_id, reward_amount, claim_deadline) => {
+                env.invoke_contract::<()>(
+                    &config.token,
+                    &Symbol::new(&env, "set_event_reward"),
+                    (this, event_id, reward_amount, claim_deadline).into_val(&env),
+                );
+            }
+            ProposalAction::TreasuryTransfer(to, amount) => {
+                env.invoke_contract::<()>(
+                    &config.token,
+                    &Symbol::new(&env, "transfer"),
+                    (this, to, amount).into_val(&env),
+                );
+            }
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "proposal_executed"), proposal_id),
+            winner,
+        );
+    }
+
+    /// Delegate voting power to another account. Delegation is one hop only:
+    /// power delegated to `delegatee` is not passed on by its own delegation.
+    pub fn delegate(env: Env, delegator: Address, delegatee: Address) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        delegator.require_auth();
+
+        if delegator == delegatee {
+            panic!("Cannot delegate to yourself");
+        }
+
+        let config = Self::get_config(env.clone());
+        if Self::balance(&env, &config, &delegator) <= 0 {
+            panic!("No voting power to delegate");
+        }
+
+        Self::remove_delegation(&env, &delegator);
+
+        let mut delegators = Self::get_delegators(env.clone(), delegatee.clone());
+        if delegators.len() >= MAX_DELEGATORS {
+            delegators = Self::drop_empty_delegators(&env, &config, delegators);
+        }
+        if delegators.len() >= MAX_DELEGATORS {
+            panic!("Delegatee has too many delegators");
+        }
+        delegators.push_back(delegator.clone());
+
+        env.storage().persistent().set(&DataKey::Delegators(delegatee.clone()), &delegators);
+        env.storage().persistent().set(&DataKey::Delegate(delegator.clone()), &delegatee);
+
+        env.events().publish(
+            (Symbol::new(&env, "delegated"), delegator),
+            delegatee,
+        );
+    }
+
+    /// Take back delegated voting power
+    pub fn undelegate(env: Env, delegator: Address) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        delegator.require_auth();
+
+        if !Self::remove_delegation(&env, &delegator) {
+            panic!("Votes are not delegated");
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "undelegated"), delegator),
+            (),
+        );
+    }
+
+    /// Accept a pending TokenRewards admin nomination for this contract (anyone),
+    /// letting passed proposals call admin-only actions
+    pub fn accept_token_admin(env: Env) {
+        let config = Self::get_config(env.clone());
+        env.invoke_contract::<()>(
+            &config.token,
+            &Symbol::new(&env, "accept_admin"),
+            (env.current_contract_address(),).into_val(&env),
+        );
+    }
+
+    /// Get a proposal's current status
+    pub fn proposal_status(env: Env, proposal_id: u64) -> ProposalStatus {
+        let proposal = Self::get_proposal(env.clone(), proposal_id);
+        if proposal.executed {
+            return ProposalStatus::Executed;
+        }
+
+        if env.ledger().timestamp() < proposal.end {
+            return ProposalStatus::Active;
+        }
+
+        let config = Self::get_config(env.clone());
+        let total_votes: i128 = proposal.votes.iter().sum();
+        let supply = Self::votable_supply(&env, &config, proposal.snapshot_ledger);
+
+        let quorum = supply * config.quorum_bps as i128 / BPS_DENOMINATOR as i128;
+        let passed = match Self::winning_option(&proposal) {
+            Some(winner) => {
+                let winning_votes = proposal.votes.get_unchecked(winner);
+                total_votes > 0
+                    && total_votes >= quorum
+                    && winning_votes * BPS_DENOMINATOR as i128 >= total_votes * config.threshold_bps as i128
+            }
+            None => false,
+        };
+
+        if passed {
+            ProposalStatus::Succeeded
+        } else {
+            ProposalStatus::Defeated
+        }
+    }
+
+    /// Voting power `voter` would add to a proposal now
+    pub fn voting_power(env: Env, voter: Address, proposal_id: u64) -> i128 {
+        let proposal = Self::get_proposal(env.clone(), proposal_id);
+        let config = Self::get_config(env.clone());
+
+        let mut power = 0;
+        if !Self::is_counted(&env, proposal_id, &voter) {
+            power += Self::balance_at(&env, &config, &voter, proposal.snapshot_ledger);
+        }
+        for delegator in Self::get_delegators(env.clone(), voter).iter() {
+            if !Self::is_counted(&env, proposal_id, &delegator) {
+                power += Self::balance_at(&env, &config, &delegator, proposal.snapshot_ledger);
+            }
+        }
+        power
+    }
+
+    /// Get proposal details
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Proposal {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .expect("Proposal not found")
+    }
+
+    /// Get the number of proposals created
+    pub fn get_proposal_count(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ProposalCounter)
+            .unwrap_or(0)
+    }
+
+    /// Get the option a voter chose, if they voted
+    pub fn get_vote(env: Env, proposal_id: u64, voter: Address) -> Option<u32> {
+        env.storage().persistent().get(&DataKey::Vote(proposal_id, voter))
+    }
+
+    /// Get the account an address delegated to, if any
+    pub fn get_delegate(env: Env, delegator: Address) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Delegate(delegator))
+    }
+
+    /// Get the accounts that delegated to an address
+    pub fn get_delegators(env: Env, delegatee: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Delegators(delegatee))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the voting rules
+    pub fn get_config(env: Env) -> GovernanceConfig {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Config)
+            .expect("Governance not initialized")
+    }
+
+    /// Get current admin
+    pub fn get_admin(env: Env) -> Address {
+        ownership::get_owner(&env).expect("Admin not set")
+    }
+
+    /// Nominate a new admin; takes effect once they accept (admin only)
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address) {
+        ownership::propose(&env, &admin, &new_admin);
+    }
+
+    /// Accept a pending admin nomination
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        ownership::accept(&env, &new_admin);
+    }
+
+    /// Permanently give up the admin role (admin only)
+    pub fn renounce_admin(env: Env, admin: Address) {
+        ownership::renounce(&env, &admin);
+    }
+
+    /// Get the admin nominated but not yet accepted, if any
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        ownership::get_pending_owner(&env)
+    }
+
+    /// Appoint the pause guardian (admin only)
+    pub fn set_pause_guardian(env: Env, admin: Address, guardian: Address) {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can set the pause guardian");
+        }
+
+        pausable::set_guardian(&env, &guardian);
+    }
+
+    /// Pause a scope of entry points, optionally until `expires_at` (guardian only)
+    pub fn pause(env: Env, guardian: Address, scope: PauseScope, expires_at: Option<u64>) {
+        pausable::pause(&env, &guardian, scope, expires_at);
+    }
+
+    /// Lift a pause (guardian only)
+    pub fn unpause(env: Env, guardian: Address, scope: PauseScope) {
+        pausable::unpause(&env, &guardian, scope);
+    }
+
+    /// Check whether a scope is currently paused
+    pub fn is_paused(env: Env, scope: PauseScope) -> bool {
+        pausable::is_paused(&env, scope)
+    }
+
+    /// Get the pause guardian, if any
+    pub fn get_pause_guardian(env: Env) -> Option<Address> {
+        pausable::get_guardian(&env)
+    }
+
+    /// Replace the contract code, keeping all storage (admin only)
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can upgrade the contract");
+        }
+
+        upgradeable::upgrade(&env, new_wasm_hash);
+    }
+
+    /// Migrate up to `limit` storage entries towards the current layout (admin only)
+    /// Returns the storage version afterwards; call again until it equals `STORAGE_VERSION`
+    pub fn migrate(env: Env, admin: Address, limit: u32) -> u32 {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can migrate storage");
+        }
+
+        upgradeable::migrate(&env, STORAGE_VERSION, limit, upgradeable::no_steps)
+    }
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgradeable::get_version(&env)
+    }
+}
+
+impl Governance {
+    fn balance_at(env: &Env, config: &GovernanceConfig, account: &Address, ledger: u32) -> i128 {
+        env.invoke_contract(
+            &config.token,
+            &Symbol::new(env, "balance_at"),
+            (account.clone(), ledger).into_val(env),
+        )
+    }
+
+    fn balance(env: &Env, config: &GovernanceConfig, account: &Address) -> i128 {
+        env.invoke_contract(
+            &config.token,
+            &Symbol::new(env, "balance"),
+            (account.clone(),).into_val(env),
+        )
+    }
+
+    /// Total supply at `ledger` minus tokens that cannot vote: those held by the
+    /// TokenRewards contract (reward pools, vesting grants, stakes) and this
+    /// contract's own treasury
+    fn votable_supply(env: &Env, config: &GovernanceConfig, ledger: u32) -> i128 {
+        let supply: i128 = env.invoke_contract(
+            &config.token,
+            &Symbol::new(env, "total_supply_at"),
+            (ledger,).into_val(env),
+        );
+        supply
+            - Self::balance_at(env, config, &config.token, ledger)
+            - Self::balance_at(env, config, &env.current_contract_address(), ledger)
+    }
+
+    fn is_counted(env: &Env, proposal_id: u64, account: &Address) -> bool {
+        env.storage().persistent().has(&DataKey::Counted(proposal_id, account.clone()))
+    }
+
+    /// Sum the voter's and its delegators' snapshot balances not yet used on this
+    /// proposal, marking them used so re-delegating cannot count them twice
+    fn count_power(env: &Env, proposal: &Proposal, voter: &Address) -> i128 {
+        let config = Self::get_config(env.clone());
+
+        let mut accounts = Self::get_delegators(env.clone(), voter.clone());
+        accounts.push_front(voter.clone());
+
+        let mut power = 0;
+        for account in accounts.iter() {
+            if Self::is_counted(env, proposal.id, &account) {
+                continue;
+            }
+            power += Self::balance_at(env, &config, &account, proposal.snapshot_ledger);
+            env.storage().persistent().set(&DataKey::Counted(proposal.id, account), &true);
+        }
+        power
+    }
+
+    /// Option with the most votes; `None` on a tie for first place
+    fn winning_option(proposal: &Proposal) -> Option<u32> {
+        let mut winner: Option<u32> = None;
+        let mut best: i128 = 0;
+        let mut tied = false;
+        for (index, votes) in proposal.votes.iter().enumerate() {
+            if winner.is_none() || votes > best {
+                winner = Some(index as u32);
+                best = votes;
+                tied = false;
+            } else if votes == best {
+                tied = true;
+            }
+        }
+
+        if tied {
+            None
+        } else {
+            winner
+        }
+    }
+
+    /// Remove delegators that no longer hold tokens, clearing their delegations
+    fn drop_empty_delegators(env: &Env, config: &GovernanceConfig, delegators: Vec<Address>) -> Vec<Address> {
+        let mut remaining = Vec::new(env);
+        for delegator in delegators.iter() {
+            if Self::balance(env, config, &delegator) > 0 {
+                remaining.push_back(delegator);
+            } else {
+                env.storage().persistent().remove(&DataKey::Delegate(delegator));
+            }
+        }
+        remaining
+    }
+
+    /// Clear `delegator`'s delegation; returns whether there was one
+    fn remove_delegation(env: &Env, delegator: &Address) -> bool {
+        let previous = match Self::get_delegate(env.clone(), delegator.clone()) {
+            Some(previous) => previous,
+            None => return false,
+        };
+
+        let mut delegators = Self::get_delegators(env.clone(), previous.clone());
+        if let Some(index) = delegators.first_index_of(delegator) {
+            delegators.remove(index);
+        }
+        env.storage().persistent().set(&DataKey::Delegators(previous), &delegators);
+        env.storage().persistent().remove(&DataKey::Delegate(delegator.clone()));
+        true
+    }
+}
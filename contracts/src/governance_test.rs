@@ -0,0 +1,141 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::vec;
+use token_rewards::{TokenRewards, TokenRewardsClient};
+
+struct Setup<'a> {
+    env: Env,
+    governance: GovernanceClient<'a>,
+    rewards: TokenRewardsClient<'a>,
+    admin: Address,
+}
+
+/// Governance over a TokenRewards supply of 1_000_000 held by the admin, with a
+/// 100 second voting period, 10% quorum and a simple majority
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+    env.ledger().set_timestamp(1_000);
+    env.ledger().set_sequence_number(10);
+
+    let admin = Address::generate(&env);
+    let rewards = TokenRewardsClient::new(&env, &env.register(TokenRewards, ()));
+    let text = String::from_str(&env, "Kaizen");
+    rewards.init(&admin, &text, &text, &7, &1_000_000, &10_000_000);
+
+    let governance = GovernanceClient::new(&env, &env.register(Governance, ()));
+    governance.init(&admin, &GovernanceConfig {
+        token: rewards.address.clone(),
+        voting_period: 100,
+        proposal_threshold: 0,
+        quorum_bps: 1_000,
+        threshold_bps: 5_000,
+    });
+
+    Setup { env, governance, rewards, admin }
+}
+
+impl Setup<'_> {
+    fn funded(&self, amount: i128) -> Address {
+        let account = Address::generate(&self.env);
+        self.rewards.transfer(&self.admin, &account, &amount);
+        account
+    }
+
+    /// Open a yes/no proposal snapshotting the ledger before this one
+    fn propose(&self) -> u64 {
+        self.env.ledger().set_sequence_number(self.env.ledger().sequence() + 1);
+        let label = String::from_str(&self.env, "Option");
+        let option = ProposalOption { label, action: ProposalAction::None };
+        let options = vec![&self.env, option.clone(), option];
+        self.governance.propose(&self.admin, &BytesN::from_array(&self.env, &[0; 32]), &options)
+    }
+}
+
+#[test]
+fn test_votes_use_snapshot_balances() {
+    let s = setup();
+    let voter = s.funded(60_000);
+    let proposal_id = s.propose();
+
+    // Tokens moved after the snapshot carry no votes with them
+    let buyer = Address::generate(&s.env);
+    s.rewards.transfer(&voter, &buyer, &60_000);
+    assert!(s.governance.try_vote(&buyer, &proposal_id, &0).is_err());
+    assert_eq!(s.governance.vote(&voter, &proposal_id, &0), 60_000);
+}
+
+#[test]
+fn test_delegation_after_snapshot_counts_once() {
+    let s = setup();
+    let delegator = s.funded(60_000);
+    let delegatee = s.funded(40_000);
+    let other = s.funded(1_000);
+    let proposal_id = s.propose();
+
+    s.governance.delegate(&delegator, &delegatee);
+    assert_eq!(s.governance.voting_power(&delegatee, &proposal_id), 100_000);
+    assert_eq!(s.governance.vote(&delegatee, &proposal_id, &0), 100_000);
+
+    // Moving the delegation or voting directly cannot reuse the counted power
+    s.governance.delegate(&delegator, &other);
+    assert_eq!(s.governance.vote(&other, &proposal_id, &1), 1_000);
+    s.governance.undelegate(&delegator);
+    assert!(s.governance.try_vote(&delegator, &proposal_id, &1).is_err());
+
+    let proposal = s.governance.get_proposal(&proposal_id);
+    assert_eq!(proposal.votes, vec![&s.env, 100_000, 1_000]);
+}
+
+#[test]
+fn test_delegators_cannot_vote_twice() {
+    let s = setup();
+    let delegator = s.funded(60_000);
+    let delegatee = s.funded(40_000);
+    s.governance.delegate(&delegator, &delegatee);
+    let proposal_id = s.propose();
+
+    assert!(s.governance.try_vote(&delegator, &proposal_id, &1).is_err());
+    s.governance.vote(&delegatee, &proposal_id, &0);
+    assert!(s.governance.try_vote(&delegatee, &proposal_id, &0).is_err());
+    assert_eq!(s.governance.get_proposal(&proposal_id).votes, vec![&s.env, 100_000, 0]);
+}
+
+#[test]
+fn test_full_delegator_list_evicts_empty_accounts() {
+    let s = setup();
+    let delegatee = s.funded(1);
+    let mut delegators = Vec::new(&s.env);
+    for _ in 0..MAX_DELEGATORS {
+        let delegator = s.funded(10);
+        s.governance.delegate(&delegator, &delegatee);
+        delegators.push_back(delegator);
+    }
+
+    let late = s.funded(10);
+    assert!(s.governance.try_delegate(&late, &delegatee).is_err());
+
+    // Once a delegator holds no tokens, its slot is given up
+    let emptied = delegators.get_unchecked(0);
+    s.rewards.transfer(&emptied, &s.admin, &10);
+    s.governance.delegate(&late, &delegatee);
+    assert_eq!(s.governance.get_delegators(&delegatee).len(), MAX_DELEGATORS);
+    assert_eq!(s.governance.get_delegate(&emptied), None);
+    assert_eq!(s.governance.get_delegate(&late), Some(delegatee));
+}
+
+#[test]
+fn test_quorum_counts_only_votable_supply() {
+    let s = setup();
+    // Tokens held in TokenRewards and the governance treasury cannot vote
+    s.rewards.fund_reward_pool(&s.admin, &1, &900_000, &10, &5_000);
+    s.rewards.transfer(&s.admin, &s.governance.address, &50_000);
+    let voter = s.funded(5_000);
+    let proposal_id = s.propose();
+
+    // 5_000 is 10% of the 50_000 votable, though only 0.5% of the supply
+    s.governance.vote(&voter, &proposal_id, &0);
+    s.env.ledger().set_timestamp(1_100);
+    assert!(s.governance.proposal_status(&proposal_id) == ProposalStatus::Succeeded);
+}
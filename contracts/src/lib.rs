@@ -1,19 +1,26 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, Address, Env, Symbol, Vec, String, IntoVal,
+    contract, contractimpl, contracttype, Address, Env, Symbol, String, IntoVal, Val, BytesN,
 };
 
+mod ownership;
+mod pausable;
+mod upgradeable;
+
+pub use pausable::PauseScope;
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
-    Initialized,
-    Organizer,
     Name,
     TokenAddr,        // optional: token/NFT contract address to mint on join
     Joined(Address),  // per-attendee marker
     JoinCount,
 }
 
+/// Storage layout version written by this code.
+const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct KaizenEvent;
 
@@ -27,7 +34,7 @@ pub struct TokenClient;
 impl TokenLike for TokenClient {
     fn mint(env: &Env, token: &Address, to: &Address, amount: i128) {
         // Calls token contract's `mint`(to, amount). Adjust name if using NFT impl.
-        let _ = env.invoke_contract(
+        let _: Val = env.invoke_contract(
             token,
             &Symbol::new(env, "mint"),
             (to.clone(), amount).into_val(env),
@@ -40,13 +47,10 @@ impl KaizenEvent {
     /// One-time initializer for an event instance.
     /// `token` is optional: if Some, contract will mint 1 unit to attendee on join.
     pub fn init(e: Env, organizer: Address, name: String, token: Option<Address>) {
-        if e.storage().instance().has(&DataKey::Initialized) {
-            panic!("already initialized");
-        }
-        organizer.require_auth(); // organizer must authorize init
+        // organizer must authorize init; panics if already initialized
+        ownership::init(&e, &organizer);
+        upgradeable::set_version(&e, STORAGE_VERSION);
 
-        e.storage().instance().set(&DataKey::Initialized, &true);
-        e.storage().instance().set(&DataKey::Organizer, &organizer);
         e.storage().instance().set(&DataKey::Name, &name);
         if let Some(t) = token {
             e.storage().instance().set(&DataKey::TokenAddr, &t);
@@ -59,10 +63,11 @@ impl KaizenEvent {
         );
     }
 
-    /// Returns tuple: (name, organizer, token_addr_opt, join_count)
-    pub fn info(e: Env) -> (String, Address, Option<Address>, i128) {
+    /// Returns tuple: (name, organizer_opt, token_addr_opt, join_count)
+    /// `organizer_opt` is None once the organizer has renounced.
+    pub fn info(e: Env) -> (String, Option<Address>, Option<Address>, i128) {
         let name: String = e.storage().instance().get(&DataKey::Name).unwrap();
-        let organizer: Address = e.storage().instance().get(&DataKey::Organizer).unwrap();
+        let organizer = ownership::get_owner(&e);
         let token: Option<Address> = e.storage().instance().get(&DataKey::TokenAddr).unwrap_or(None);
         let count: i128 = e.storage().instance().get(&DataKey::JoinCount).unwrap_or(0i128);
         (name, organizer, token, count)
@@ -73,6 +78,8 @@ impl KaizenEvent {
     /// - prevents double-join
     /// - optionally mints 1 unit from token/NFT contract to attendee
     pub fn join(e: Env, attendee: Address) {
+        upgradeable::require_migrated(&e, STORAGE_VERSION);
+        pausable::when_not_paused(&e, PauseScope::Purchases);
         attendee.require_auth(); // host-managed auth
 
         let joined_key = DataKey::Joined(attendee.clone());
@@ -107,9 +114,76 @@ impl KaizenEvent {
 
     /// Optional: only organizer can set/replace token later.
     pub fn set_token(e: Env, caller: Address, token: Address) {
-        let org: Address = e.storage().instance().get(&DataKey::Organizer).unwrap();
+        upgradeable::require_migrated(&e, STORAGE_VERSION);
         caller.require_auth();
-        if caller != org { panic!("only organizer"); }
+        if !ownership::is_owner(&e, &caller) { panic!("only organizer"); }
         e.storage().instance().set(&DataKey::TokenAddr, &token);
     }
+
+    /// Nominate a new organizer; takes effect once they accept.
+    pub fn propose_organizer(e: Env, organizer: Address, new_organizer: Address) {
+        ownership::propose(&e, &organizer, &new_organizer);
+    }
+
+    /// Accept a pending organizer nomination.
+    pub fn accept_organizer(e: Env, new_organizer: Address) {
+        ownership::accept(&e, &new_organizer);
+    }
+
+    /// Permanently give up the organizer role.
+    pub fn renounce_organizer(e: Env, organizer: Address) {
+        ownership::renounce(&e, &organizer);
+    }
+
+    /// Organizer nominated but not yet accepted, if any.
+    pub fn pending_organizer(e: Env) -> Option<Address> {
+        ownership::get_pending_owner(&e)
+    }
+
+    /// Appoint the pause guardian (organizer only).
+    pub fn set_pause_guardian(e: Env, caller: Address, guardian: Address) {
+        caller.require_auth();
+        if !ownership::is_owner(&e, &caller) { panic!("only organizer"); }
+        pausable::set_guardian(&e, &guardian);
+    }
+
+    /// Pause a scope of entry points, optionally until `expires_at` (guardian only).
+    pub fn pause(e: Env, guardian: Address, scope: PauseScope, expires_at: Option<u64>) {
+        pausable::pause(&e, &guardian, scope, expires_at);
+    }
+
+    /// Lift a pause (guardian only).
+    pub fn unpause(e: Env, guardian: Address, scope: PauseScope) {
+        pausable::unpause(&e, &guardian, scope);
+    }
+
+    /// Check whether a scope is currently paused.
+    pub fn is_paused(e: Env, scope: PauseScope) -> bool {
+        pausable::is_paused(&e, scope)
+    }
+
+    /// Pause guardian, if any.
+    pub fn pause_guardian(e: Env) -> Option<Address> {
+        pausable::get_guardian(&e)
+    }
+
+    /// Replace the contract code, keeping all storage (organizer only).
+    pub fn upgrade(e: Env, organizer: Address, new_wasm_hash: BytesN<32>) {
+        organizer.require_auth();
+        if !ownership::is_owner(&e, &organizer) { panic!("only organizer"); }
+        upgradeable::upgrade(&e, new_wasm_hash);
+    }
+
+    /// Migrate up to `limit` storage entries towards the current layout (organizer only).
+    /// Returns the storage version afterwards; call again until it equals `STORAGE_VERSION`.
+    pub fn migrate(e: Env, organizer: Address, limit: u32) -> u32 {
+        organizer.require_auth();
+        if !ownership::is_owner(&e, &organizer) { panic!("only organizer"); }
+        upgradeable::migrate(&e, STORAGE_VERSION, limit, upgradeable::no_steps)
+    }
+
+    /// Get the storage layout version.
+    pub fn version(e: Env) -> u32 {
+        upgradeable::get_version(&e)
+    }
 }
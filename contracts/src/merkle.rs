@@ -0,0 +1,87 @@
+//! Merkle trees over `(index, address, amount)` reward entries.
+//!
+//! `leaf` and `verify` run on-chain. `root` and `proof` are the off-chain builder,
+//! exported as `token_rewards::merkle` for host-side tools: they need only an `Env`
+//! (e.g. `Env::default()`), so roots published by the admin and proofs handed to
+//! users hash exactly like the contract.
+//!
+//! Leaves are `sha256(0x00 || index || address xdr || amount)` and inner nodes are
+//! `sha256(0x01 || min(a, b) || max(a, b))`, so proofs carry no left/right flags.
+//! On an odd level the last node is carried up unchanged.
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Hash of one entry; `index` is the entry's position and its claim bit.
+pub fn leaf(env: &Env, index: u32, account: &Address, amount: i128) -> BytesN<32> {
+    let mut data = Bytes::from_array(env, &[LEAF_PREFIX]);
+    data.extend_from_array(&index.to_be_bytes());
+    data.append(&account.clone().to_xdr(env));
+    data.extend_from_array(&amount.to_be_bytes());
+    env.crypto().sha256(&data).to_bytes()
+}
+
+fn node(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = Bytes::from_array(env, &[NODE_PREFIX]);
+    data.append(&Bytes::from(low.clone()));
+    data.append(&Bytes::from(high.clone()));
+    env.crypto().sha256(&data).to_bytes()
+}
+
+/// Whether `proof` links `leaf` to `root`.
+pub fn verify(env: &Env, root: &BytesN<32>, leaf: &BytesN<32>, proof: &Vec<BytesN<32>>) -> bool {
+    let mut hash = leaf.clone();
+    for sibling in proof.iter() {
+        hash = node(env, &hash, &sibling);
+    }
+    hash == *root
+}
+
+fn next_level(env: &Env, level: &Vec<BytesN<32>>) -> Vec<BytesN<32>> {
+    let mut parents = Vec::new(env);
+    let mut i = 0;
+    while i < level.len() {
+        if i + 1 < level.len() {
+            parents.push_back(node(env, &level.get_unchecked(i), &level.get_unchecked(i + 1)));
+        } else {
+            parents.push_back(level.get_unchecked(i));
+        }
+        i += 2;
+    }
+    parents
+}
+
+/// Root over `leaves`, in index order (off-chain builder).
+pub fn root(env: &Env, leaves: &Vec<BytesN<32>>) -> BytesN<32> {
+    if leaves.is_empty() {
+        panic!("Merkle tree needs at least one leaf");
+    }
+
+    let mut level = leaves.clone();
+    while level.len() > 1 {
+        level = next_level(env, &level);
+    }
+    level.get_unchecked(0)
+}
+
+/// Proof for the leaf at `index` (off-chain builder).
+pub fn proof(env: &Env, leaves: &Vec<BytesN<32>>, index: u32) -> Vec<BytesN<32>> {
+    if index >= leaves.len() {
+        panic!("Leaf index out of range");
+    }
+
+    let mut siblings = Vec::new(env);
+    let mut level = leaves.clone();
+    let mut position = index;
+    while level.len() > 1 {
+        let sibling = position ^ 1;
+        if sibling < level.len() {
+            siblings.push_back(level.get_unchecked(sibling));
+        }
+        level = next_level(env, &level);
+        position /= 2;
+    }
+    siblings
+}
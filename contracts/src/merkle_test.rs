@@ -0,0 +1,107 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+
+struct Setup<'a> {
+    env: Env,
+    rewards: TokenRewardsClient<'a>,
+    admin: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let rewards = TokenRewardsClient::new(&env, &env.register(TokenRewards, ()));
+    let text = String::from_str(&env, "Kaizen");
+    rewards.init(&admin, &text, &text, &7, &1_000_000, &10_000_000);
+
+    Setup { env, rewards, admin }
+}
+
+impl Setup<'_> {
+    /// Leaves paying `amounts` to fresh accounts, in index order
+    fn entries(&self, amounts: &[i128]) -> (Vec<Address>, Vec<BytesN<32>>) {
+        let mut accounts = Vec::new(&self.env);
+        let mut leaves = Vec::new(&self.env);
+        for (index, amount) in amounts.iter().enumerate() {
+            let account = Address::generate(&self.env);
+            leaves.push_back(merkle::leaf(&self.env, index as u32, &account, *amount));
+            accounts.push_back(account);
+        }
+        (accounts, leaves)
+    }
+
+    fn drop(&self, leaves: &Vec<BytesN<32>>, total: i128) -> u64 {
+        let root = merkle::root(&self.env, leaves);
+        self.rewards.create_merkle_drop(&self.admin, &root, &total, &2_000, &None)
+    }
+}
+
+#[test]
+fn test_single_leaf_is_root() {
+    let s = setup();
+    let (accounts, leaves) = s.entries(&[50]);
+    assert_eq!(merkle::root(&s.env, &leaves), leaves.get_unchecked(0));
+    let proof = merkle::proof(&s.env, &leaves, 0);
+    assert!(proof.is_empty());
+
+    let drop_id = s.drop(&leaves, 50);
+    let account = accounts.get_unchecked(0);
+    assert_eq!(s.rewards.claim_merkle_drop(&account, &drop_id, &0, &50, &proof), 50);
+    assert_eq!(s.rewards.balance(&account), 50);
+}
+
+#[test]
+fn test_odd_level_carries_last_node_up() {
+    let s = setup();
+    let (accounts, leaves) = s.entries(&[10, 20, 30]);
+    let root = merkle::root(&s.env, &leaves);
+    for index in 0..3 {
+        let proof = merkle::proof(&s.env, &leaves, index);
+        assert!(merkle::verify(&s.env, &root, &leaves.get_unchecked(index), &proof));
+    }
+    // The third leaf has no sibling on the first level
+    assert_eq!(merkle::proof(&s.env, &leaves, 2).len(), 1);
+
+    let drop_id = s.drop(&leaves, 60);
+    let proof = merkle::proof(&s.env, &leaves, 2);
+    assert_eq!(s.rewards.claim_merkle_drop(&accounts.get_unchecked(2), &drop_id, &2, &30, &proof), 30);
+}
+
+#[test]
+fn test_tampered_claims_are_rejected() {
+    let s = setup();
+    let (accounts, leaves) = s.entries(&[10, 20, 30, 40]);
+    let drop_id = s.drop(&leaves, 100);
+    let account = accounts.get_unchecked(1);
+    let proof = merkle::proof(&s.env, &leaves, 1);
+
+    assert!(s.rewards.try_claim_merkle_drop(&account, &drop_id, &1, &21, &proof).is_err());
+    assert!(s.rewards.try_claim_merkle_drop(&account, &drop_id, &0, &20, &proof).is_err());
+    let other = accounts.get_unchecked(0);
+    assert!(s.rewards.try_claim_merkle_drop(&other, &drop_id, &1, &20, &proof).is_err());
+    assert_eq!(s.rewards.claim_merkle_drop(&account, &drop_id, &1, &20, &proof), 20);
+}
+
+#[test]
+fn test_double_claim_is_rejected() {
+    let s = setup();
+    let (accounts, leaves) = s.entries(&[10, 20]);
+    let drop_id = s.drop(&leaves, 30);
+    let account = accounts.get_unchecked(0);
+    let proof = merkle::proof(&s.env, &leaves, 0);
+
+    s.rewards.claim_merkle_drop(&account, &drop_id, &0, &10, &proof);
+    assert!(s.rewards.is_merkle_claimed(&drop_id, &0));
+    assert!(!s.rewards.is_merkle_claimed(&drop_id, &1));
+    assert!(s.rewards.try_claim_merkle_drop(&account, &drop_id, &0, &10, &proof).is_err());
+    assert_eq!(s.rewards.balance(&account), 10);
+
+    // Claims stay independent in the bitmap
+    let other = accounts.get_unchecked(1);
+    let proof = merkle::proof(&s.env, &leaves, 1);
+    assert_eq!(s.rewards.claim_merkle_drop(&other, &drop_id, &1, &20, &proof), 20);
+    assert_eq!(s.rewards.get_merkle_drop(&drop_id).claimed, 30);
+}
@@ -1,5 +1,11 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, Bytes, log};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, BytesN, log};
+
+mod ownership;
+mod pausable;
+mod upgradeable;
+
+pub use pausable::PauseScope;
 
 #[derive(Clone)]
 #[contracttype]
@@ -19,9 +25,11 @@ pub enum DataKey {
     TokenMetadata(u64),
     OwnerTokens(Address),
     EventNFTs(u64),
-    Admin,
 }
 
+/// Storage layout version written by this code
+const STORAGE_VERSION: u32 = 1;
+
 #[contract]
 pub struct NFTMinter;
 
@@ -29,10 +37,13 @@ pub struct NFTMinter;
 impl NFTMinter {
     /// Initialize the NFT contract
     pub fn init(env: Env, admin: Address) {
+        // Admin must authorize; panics if already initialized
+        ownership::init(&env, &admin);
+        upgradeable::set_version(&env, STORAGE_VERSION);
+
         let counter: u64 = 0;
         env.storage().persistent().set(&DataKey::TokenCounter, &counter);
-        env.storage().persistent().set(&DataKey::Admin, &admin);
-        
+
         log!(&env, "NFT Minter initialized with admin: {}", admin);
     }
 
@@ -45,11 +56,10 @@ impl NFTMinter {
         description: String,
         image: String,
     ) -> u64 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Mints);
         // Only admin can mint for now (in production, this would be the event manager contract)
-        let admin: Address = env.storage()
-            .persistent()
-            .get(&DataKey::Admin)
-            .expect("Admin not set");
+        let admin: Address = ownership::get_owner(&env).expect("Admin not set");
         admin.require_auth();
 
         // Get and increment token counter
@@ -103,10 +113,9 @@ impl NFTMinter {
         description: String,
         image: String,
     ) -> Vec<u64> {
-        let admin: Address = env.storage()
-            .persistent()
-            .get(&DataKey::Admin)
-            .expect("Admin not set");
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Mints);
+        let admin: Address = ownership::get_owner(&env).expect("Admin not set");
         admin.require_auth();
 
         let mut token_ids: Vec<u64> = Vec::new(&env);
@@ -174,6 +183,8 @@ impl NFTMinter {
         to: Address,
         token_id: u64,
     ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Transfers);
         from.require_auth();
 
         let current_owner: Address = env.storage()
@@ -189,7 +200,7 @@ impl NFTMinter {
         env.storage().persistent().set(&DataKey::TokenOwner(token_id), &to);
 
         // Update from owner's token list
-        let mut from_tokens: Vec<u64> = env.storage()
+        let from_tokens: Vec<u64> = env.storage()
             .persistent()
             .get(&DataKey::OwnerTokens(from.clone()))
             .unwrap_or(Vec::new(&env));
@@ -219,28 +230,87 @@ impl NFTMinter {
         env.storage().persistent().has(&DataKey::TokenOwner(token_id))
     }
 
-    /// Update admin (only current admin)
-    pub fn update_admin(env: Env, current_admin: Address, new_admin: Address) {
-        current_admin.require_auth();
+    /// Get current admin
+    pub fn get_admin(env: Env) -> Address {
+        ownership::get_owner(&env).expect("Admin not set")
+    }
 
-        let stored_admin: Address = env.storage()
-            .persistent()
-            .get(&DataKey::Admin)
-            .expect("Admin not set");
+    /// Nominate a new admin; takes effect once they accept (admin only)
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address) {
+        ownership::propose(&env, &admin, &new_admin);
+    }
+
+    /// Accept a pending admin nomination
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        ownership::accept(&env, &new_admin);
+    }
 
-        if stored_admin != current_admin {
-            panic!("Only admin can update admin");
+    /// Permanently give up the admin role (admin only)
+    pub fn renounce_admin(env: Env, admin: Address) {
+        ownership::renounce(&env, &admin);
+    }
+
+    /// Get the admin nominated but not yet accepted, if any
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        ownership::get_pending_owner(&env)
+    }
+
+    /// Appoint the pause guardian (admin only)
+    pub fn set_pause_guardian(env: Env, admin: Address, guardian: Address) {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can set the pause guardian");
         }
 
-        env.storage().persistent().set(&DataKey::Admin, &new_admin);
-        log!(&env, "Admin updated to: {}", new_admin);
+        pausable::set_guardian(&env, &guardian);
     }
 
-    /// Get current admin
-    pub fn get_admin(env: Env) -> Address {
-        env.storage()
-            .persistent()
-            .get(&DataKey::Admin)
-            .expect("Admin not set")
+    /// Pause a scope of entry points, optionally until `expires_at` (guardian only)
+    pub fn pause(env: Env, guardian: Address, scope: PauseScope, expires_at: Option<u64>) {
+        pausable::pause(&env, &guardian, scope, expires_at);
+    }
+
+    /// Lift a pause (guardian only)
+    pub fn unpause(env: Env, guardian: Address, scope: PauseScope) {
+        pausable::unpause(&env, &guardian, scope);
+    }
+
+    /// Check whether a scope is currently paused
+    pub fn is_paused(env: Env, scope: PauseScope) -> bool {
+        pausable::is_paused(&env, scope)
+    }
+
+    /// Get the pause guardian, if any
+    pub fn get_pause_guardian(env: Env) -> Option<Address> {
+        pausable::get_guardian(&env)
+    }
+
+    /// Replace the contract code, keeping all storage (admin only)
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can upgrade the contract");
+        }
+
+        upgradeable::upgrade(&env, new_wasm_hash);
+    }
+
+    /// Migrate up to `limit` storage entries towards the current layout (admin only)
+    /// Returns the storage version afterwards; call again until it equals `STORAGE_VERSION`
+    pub fn migrate(env: Env, admin: Address, limit: u32) -> u32 {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can migrate storage");
+        }
+
+        upgradeable::migrate(&env, STORAGE_VERSION, limit, upgradeable::no_steps)
+    }
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgradeable::get_version(&env)
     }
 }
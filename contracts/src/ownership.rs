@@ -0,0 +1,87 @@
+//! Shared one-time initialization and two-step ownership handover.
+//!
+//! Every contract keeps its owner (admin / organizer) here, in instance storage.
+//! A handover only takes effect once the proposed owner accepts it, and renouncing
+//! leaves the contract without an owner for good: `init` can never run twice.
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+#[derive(Clone)]
+#[contracttype]
+pub enum OwnershipKey {
+    Initialized,
+    Owner,
+    PendingOwner,
+}
+
+/// Guarded initializer: panics if already initialized, requires `owner` auth.
+pub fn init(env: &Env, owner: &Address) {
+    if env.storage().instance().has(&OwnershipKey::Initialized) {
+        panic!("already initialized");
+    }
+    owner.require_auth();
+
+    env.storage().instance().set(&OwnershipKey::Initialized, &true);
+    env.storage().instance().set(&OwnershipKey::Owner, owner);
+}
+
+/// Current owner, `None` once renounced or before init.
+pub fn get_owner(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&OwnershipKey::Owner)
+}
+
+/// Owner proposed by `propose`, waiting to accept.
+pub fn get_pending_owner(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&OwnershipKey::PendingOwner)
+}
+
+pub fn is_owner(env: &Env, addr: &Address) -> bool {
+    get_owner(env).is_some_and(|owner| owner == *addr)
+}
+
+/// Step one of a handover: the current owner nominates `new_owner`.
+pub fn propose(env: &Env, owner: &Address, new_owner: &Address) {
+    owner.require_auth();
+    if !is_owner(env, owner) {
+        panic!("Only owner can propose a new owner");
+    }
+
+    env.storage().instance().set(&OwnershipKey::PendingOwner, new_owner);
+
+    env.events().publish(
+        (Symbol::new(env, "ownership"), Symbol::new(env, "proposed")),
+        (owner.clone(), new_owner.clone()),
+    );
+}
+
+/// Step two of a handover: the nominee takes over.
+pub fn accept(env: &Env, new_owner: &Address) {
+    new_owner.require_auth();
+    if get_pending_owner(env).as_ref() != Some(new_owner) {
+        panic!("No pending ownership for this address");
+    }
+
+    let previous = get_owner(env);
+    env.storage().instance().set(&OwnershipKey::Owner, new_owner);
+    env.storage().instance().remove(&OwnershipKey::PendingOwner);
+
+    env.events().publish(
+        (Symbol::new(env, "ownership"), Symbol::new(env, "accepted")),
+        (previous, new_owner.clone()),
+    );
+}
+
+/// Give up ownership permanently, cancelling any pending handover.
+pub fn renounce(env: &Env, owner: &Address) {
+    owner.require_auth();
+    if !is_owner(env, owner) {
+        panic!("Only owner can renounce ownership");
+    }
+
+    env.storage().instance().remove(&OwnershipKey::Owner);
+    env.storage().instance().remove(&OwnershipKey::PendingOwner);
+
+    env.events().publish(
+        (Symbol::new(env, "ownership"), Symbol::new(env, "renounced")),
+        owner.clone(),
+    );
+}
@@ -0,0 +1,82 @@
+//! Shared emergency stop, operated by a pause guardian.
+//!
+//! The guardian can pause every state-changing entry point (`PauseScope::All`) or
+//! a single category, optionally until an expiry timestamp. Views, pause controls
+//! and owner-only configuration stay available while paused.
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+/// Category of entry points a pause applies to
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[contracttype]
+pub enum PauseScope {
+    All,
+    Purchases,
+    Claims,
+    Transfers,
+    Mints,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum PauseKey {
+    Guardian,
+    Paused(PauseScope), // -> expiry timestamp, u64::MAX if none
+}
+
+pub fn set_guardian(env: &Env, guardian: &Address) {
+    env.storage().instance().set(&PauseKey::Guardian, guardian);
+}
+
+pub fn get_guardian(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&PauseKey::Guardian)
+}
+
+fn require_guardian(env: &Env, guardian: &Address) {
+    guardian.require_auth();
+    if get_guardian(env).as_ref() != Some(guardian) {
+        panic!("Only pause guardian can pause or unpause");
+    }
+}
+
+/// Pause `scope` until `expires_at`, or until unpaused if `None`.
+pub fn pause(env: &Env, guardian: &Address, scope: PauseScope, expires_at: Option<u64>) {
+    require_guardian(env, guardian);
+
+    let expiry = expires_at.unwrap_or(u64::MAX);
+    env.storage().instance().set(&PauseKey::Paused(scope), &expiry);
+
+    env.events().publish(
+        (Symbol::new(env, "pause"), Symbol::new(env, "paused")),
+        (scope, expires_at),
+    );
+}
+
+pub fn unpause(env: &Env, guardian: &Address, scope: PauseScope) {
+    require_guardian(env, guardian);
+
+    env.storage().instance().remove(&PauseKey::Paused(scope));
+
+    env.events().publish(
+        (Symbol::new(env, "pause"), Symbol::new(env, "unpaused")),
+        scope,
+    );
+}
+
+/// Whether `scope` is paused, either directly or through `PauseScope::All`.
+pub fn is_paused(env: &Env, scope: PauseScope) -> bool {
+    let now = env.ledger().timestamp();
+    let active = |s: PauseScope| {
+        env.storage()
+            .instance()
+            .get::<_, u64>(&PauseKey::Paused(s))
+            .is_some_and(|expiry| now < expiry)
+    };
+
+    active(PauseScope::All) || active(scope)
+}
+
+pub fn when_not_paused(env: &Env, scope: PauseScope) {
+    if is_paused(env, scope) {
+        panic!("Contract is paused");
+    }
+}
@@ -0,0 +1,84 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+
+struct Setup<'a> {
+    env: Env,
+    manager: EventManagerClient<'a>,
+    organizer: Address,
+    event_id: u64,
+}
+
+/// A free event starting at 5_000 with no per-wallet limit
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let asset = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let manager = EventManagerClient::new(&env, &env.register(EventManager, ()));
+    manager.init(&admin, &asset);
+
+    let organizer = Address::generate(&env);
+    let text = String::from_str(&env, "Meetup");
+    let event_id = manager.create_event(&organizer, &text, &text, &5_000, &text, &0, &10, &0, &None);
+    manager.set_max_per_wallet(&organizer, &event_id, &0);
+
+    Setup { env, manager, organizer, event_id }
+}
+
+#[test]
+fn test_new_address_scores_neutral() {
+    let s = setup();
+    let newcomer = Address::generate(&s.env);
+    assert_eq!(s.manager.get_reputation(&newcomer), 50);
+}
+
+#[test]
+fn test_only_accepted_gifts_count() {
+    let s = setup();
+    let giver = Address::generate(&s.env);
+    let holder = Address::generate(&s.env);
+    let ignored = s.manager.gift_ticket(&giver, &holder, &s.event_id, &0, &None);
+    let accepted = s.manager.gift_ticket(&giver, &holder, &s.event_id, &0, &None);
+
+    assert!(s.manager.try_accept_gift(&giver, &ignored).is_err());
+    s.manager.accept_gift(&holder, &accepted);
+
+    s.env.ledger().set_timestamp(6_000);
+    s.manager.record_no_shows(&s.event_id, &10);
+    assert_eq!(s.manager.get_attendee_stats(&holder).no_shows, 1);
+    assert!(s.manager.get_reputation(&holder) < 50);
+}
+
+#[test]
+fn test_gift_cannot_be_accepted_once_event_starts() {
+    let s = setup();
+    let giver = Address::generate(&s.env);
+    let holder = Address::generate(&s.env);
+    let ticket_id = s.manager.gift_ticket(&giver, &holder, &s.event_id, &0, &None);
+
+    // Accepting at the door would let the holder count only the events they attend
+    s.env.ledger().set_timestamp(5_000);
+    assert!(s.manager.try_accept_gift(&holder, &ticket_id).is_err());
+    s.manager.check_in(&s.organizer, &ticket_id);
+    assert_eq!(s.manager.get_attendee_stats(&holder).events_attended, 0);
+}
+
+#[test]
+fn test_staff_earn_no_attendance_at_own_events() {
+    let s = setup();
+    let scanner = Address::generate(&s.env);
+    s.manager.grant_role(&s.organizer, &s.event_id, &scanner, &Role::Scanner);
+    let own = s.manager.purchase_ticket(&s.organizer, &s.event_id, &0, &None, &None);
+    let staff = s.manager.purchase_ticket(&scanner, &s.event_id, &0, &None, &None);
+    let guest = Address::generate(&s.env);
+    let guest_ticket = s.manager.purchase_ticket(&guest, &s.event_id, &0, &None, &None);
+
+    s.manager.check_in(&s.organizer, &own);
+    s.manager.check_in(&scanner, &staff);
+    s.manager.check_in(&scanner, &guest_ticket);
+    assert_eq!(s.manager.get_attendee_stats(&s.organizer).events_attended, 0);
+    assert_eq!(s.manager.get_attendee_stats(&scanner).events_attended, 0);
+    assert_eq!(s.manager.get_attendee_stats(&guest).events_attended, 1);
+}
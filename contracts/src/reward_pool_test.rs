@@ -0,0 +1,100 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::vec;
+
+struct Setup<'a> {
+    env: Env,
+    rewards: TokenRewardsClient<'a>,
+    admin: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let rewards = TokenRewardsClient::new(&env, &env.register(TokenRewards, ()));
+    let text = String::from_str(&env, "Kaizen");
+    rewards.init(&admin, &text, &text, &7, &1_000_000, &10_000_000);
+
+    Setup { env, rewards, admin }
+}
+
+impl Setup<'_> {
+    fn funded(&self, amount: i128) -> Address {
+        let account = Address::generate(&self.env);
+        self.rewards.transfer(&self.admin, &account, &amount);
+        account
+    }
+
+    /// Record `user` at `event_id` through a stand-in attendance recorder
+    fn attend(&self, user: &Address, event_id: u64) {
+        let recorder = Address::generate(&self.env);
+        self.rewards.set_attendance_recorder(&self.admin, &recorder);
+        self.rewards.record_attendance(user, &event_id);
+    }
+}
+
+#[test]
+fn test_sponsor_tops_up_and_reclaims_share() {
+    let s = setup();
+    let sponsor = s.funded(300);
+    s.rewards.fund_reward_pool(&s.admin, &1, &100, &10, &2_000);
+    s.rewards.fund_reward_pool(&sponsor, &1, &300, &0, &0);
+    assert_eq!(s.rewards.get_reward_pool(&1).budget, 400);
+    assert_eq!(s.rewards.get_pool_contribution(&1, &sponsor), 300);
+
+    let attendee = Address::generate(&s.env);
+    s.attend(&attendee, 1);
+    assert_eq!(s.rewards.claim_event_reward(&attendee, &1), 10);
+
+    s.env.ledger().set_timestamp(2_001);
+    assert!(s.rewards.try_fund_reward_pool(&sponsor, &1, &1, &0, &0).is_err());
+    let outsider = Address::generate(&s.env);
+    assert!(s.rewards.try_reclaim_reward_pool(&outsider, &1).is_err());
+
+    // 390 left, split 1:3; the last funder out takes the rounding dust
+    let admin_before = s.rewards.balance(&s.admin);
+    assert_eq!(s.rewards.reclaim_reward_pool(&s.admin, &1), 97);
+    assert_eq!(s.rewards.balance(&s.admin), admin_before + 97);
+    assert!(s.rewards.try_reclaim_reward_pool(&s.admin, &1).is_err());
+    assert_eq!(s.rewards.reclaim_reward_pool(&sponsor, &1), 293);
+    assert_eq!(s.rewards.balance(&sponsor), 293);
+    assert_eq!(s.rewards.get_reward_pool(&1).remaining, 0);
+}
+
+#[test]
+fn test_claims_need_attendance_recorder() {
+    let s = setup();
+    s.rewards.fund_reward_pool(&s.admin, &1, &100, &10, &2_000);
+    let attendee = Address::generate(&s.env);
+    assert!(s.rewards.try_claim_event_reward(&attendee, &1).is_err());
+
+    // Without a recorder the admin vouches for attendees instead
+    let paid = s.rewards.batch_distribute_rewards(&s.admin, &1, &vec![&s.env, attendee.clone()]);
+    assert_eq!(paid, vec![&s.env, 10]);
+
+    let stranger = Address::generate(&s.env);
+    s.attend(&attendee, 1);
+    assert!(s.rewards.try_claim_event_reward(&stranger, &1).is_err());
+}
+
+#[test]
+fn test_streak_bonus_is_capped() {
+    let s = setup();
+    assert!(s.rewards.try_set_streak_bonus(&s.admin, &1, &10_001, &10_000).is_err());
+    assert!(s.rewards.try_set_streak_bonus(&s.admin, &1, &5_000, &10_001).is_err());
+    s.rewards.set_streak_bonus(&s.admin, &1, &6_000, &10_000);
+
+    let attendee = Address::generate(&s.env);
+    let mut claimed = Vec::new(&s.env);
+    for event_id in 1..=3u64 {
+        s.rewards.set_event_series(&s.admin, &event_id, &1, &(event_id as u32));
+        s.rewards.fund_reward_pool(&s.admin, &event_id, &100, &10, &2_000);
+        s.attend(&attendee, event_id);
+        claimed.push_back(s.rewards.claim_event_reward(&attendee, &event_id));
+    }
+    // The bonus grows by 60% per event in a row, but never beyond doubling the reward
+    assert_eq!(claimed, vec![&s.env, 10, 16, 20]);
+}
@@ -0,0 +1,120 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+
+struct Setup<'a> {
+    env: Env,
+    manager: EventManagerClient<'a>,
+    admin: Address,
+    asset: Address,
+    organizer: Address,
+    holder: Address,
+}
+
+/// A contract whose storage was written by version 1: two events, the first
+/// with three tickets issued of which the second was refunded, and accrued fees
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let asset = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let manager = EventManagerClient::new(&env, &env.register(EventManager, ()));
+    manager.init(&admin, &asset);
+
+    let organizer = Address::generate(&env);
+    let holder = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &asset).mint(&holder, &1_000);
+    env.as_contract(&manager.address, || {
+        let storage = env.storage().persistent();
+        let text = String::from_str(&env, "Meetup");
+        for event_id in 1..=2u64 {
+            storage.set(&DataKey::Event(event_id), &EventV1 {
+                id: event_id,
+                title: text.clone(),
+                description: text.clone(),
+                organizer: organizer.clone(),
+                date: 5_000,
+                location: text.clone(),
+                price: 100,
+                max_attendees: 10,
+                current_attendees: if event_id == 1 { 2 } else { 0 },
+                is_active: true,
+                nft_contract: None,
+                token_reward_amount: 0,
+                max_per_wallet: 0,
+                ends_at: 5_000,
+                tickets_issued: if event_id == 1 { 3 } else { 0 },
+                sales_start: 0,
+            });
+        }
+        for sequence in [1u64, 3] {
+            let ticket_id = LEGACY_TICKET_STRIDE + sequence;
+            storage.set(&DataKey::Ticket(ticket_id), &TicketV1 {
+                event_id: 1,
+                attendee: holder.clone(),
+                purchaser: holder.clone(),
+                purchase_timestamp: 900,
+                ticket_id,
+                checked_in: false,
+                price_paid: 100,
+                fee_paid: 5,
+                points_paid: 0,
+            });
+        }
+        storage.set(&DataKey::EventCounter, &2u64);
+        storage.set(&DataKeyV1::AccruedFees, &10i128);
+        upgradeable::set_version(&env, 1);
+    });
+
+    Setup { env, manager, admin, asset, organizer, holder }
+}
+
+#[test]
+fn test_migration_rewrites_version_1_entries() {
+    let s = setup();
+    assert_eq!(s.manager.migrate(&s.admin, &2), 1);
+    assert_eq!(s.manager.migrate(&s.admin, &2), 1);
+    assert_eq!(s.manager.migrate(&s.admin, &2), STORAGE_VERSION);
+
+    assert_eq!(s.manager.get_event(&1).payment_asset, s.asset);
+    assert_eq!(s.manager.get_ticket(&10_003).escrowed, 95);
+    assert!(s.manager.get_ticket(&10_003).referrer.is_none());
+    assert_eq!(s.manager.get_accrued_fees(&s.asset).amount, 10);
+
+    // New ticket IDs start above every version 1 ID
+    let text = String::from_str(&s.env, "Meetup");
+    let event_id = s.manager.create_event(&s.organizer, &text, &text, &5_000, &text, &0, &10, &0, &None);
+    assert_eq!(s.manager.purchase_ticket(&s.holder, &event_id, &0, &None, &None), 30_001);
+
+    // The per-event index covers the legacy tickets, skipping the refunded one
+    s.env.ledger().set_timestamp(6_000);
+    assert_eq!(s.manager.record_no_shows(&1, &5), 0);
+    assert_eq!(s.manager.get_attendee_stats(&s.holder).no_shows, 2);
+}
+
+#[test]
+fn test_state_changes_wait_for_migration() {
+    let s = setup();
+    let text = String::from_str(&s.env, "Meetup");
+    assert_eq!(s.manager.migrate(&s.admin, &2), 1);
+
+    // Writes between batches would mix layouts, so they are rejected
+    assert!(s.manager
+        .try_create_event(&s.organizer, &text, &text, &5_000, &text, &0, &10, &0, &None)
+        .is_err());
+    assert!(s.manager.try_purchase_ticket(&s.holder, &1, &0, &None, &None).is_err());
+
+    assert_eq!(s.manager.migrate(&s.admin, &10), STORAGE_VERSION);
+    let event_id = s.manager.create_event(&s.organizer, &text, &text, &5_000, &text, &0, &10, &0, &None);
+    assert_eq!(event_id, 3);
+    s.manager.set_max_per_wallet(&s.organizer, &1, &0);
+    let ticket_id = s.manager.purchase_ticket(&s.holder, &1, &0, &Some(100), &None);
+    assert_eq!(s.manager.get_event(&1).tickets_issued, 4);
+
+    // The new ticket takes the next slot of the index without disturbing the legacy ones
+    s.env.ledger().set_timestamp(6_000);
+    assert_eq!(s.manager.record_no_shows(&1, &10), 0);
+    assert_eq!(s.manager.get_attendee_stats(&s.holder).no_shows, 3);
+    assert_eq!(s.manager.get_ticket(&ticket_id).event_id, 1);
+}
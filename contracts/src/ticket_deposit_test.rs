@@ -0,0 +1,74 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+
+struct Setup<'a> {
+    env: Env,
+    manager: EventManagerClient<'a>,
+    token: token::Client<'a>,
+    organizer: Address,
+    event_id: u64,
+}
+
+/// A free event whose tickets carry a deposit of 50, claimed back after check-in
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let asset = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let manager = EventManagerClient::new(&env, &env.register(EventManager, ()));
+    manager.init(&admin, &asset);
+
+    let organizer = Address::generate(&env);
+    let text = String::from_str(&env, "Meetup");
+    let event_id = manager.create_event(&organizer, &text, &text, &5_000, &text, &0, &10, &0, &None);
+    manager.set_deposit_config(&organizer, &event_id, &Some(DepositConfig {
+        token: asset.clone(),
+        amount: 50,
+        forfeit_to: organizer.clone(),
+        auto_return: false,
+    }));
+
+    let token = token::Client::new(&env, &asset);
+    Setup { env, manager, token, organizer, event_id }
+}
+
+impl Setup<'_> {
+    fn funded(&self, amount: i128) -> Address {
+        let account = Address::generate(&self.env);
+        token::StellarAssetClient::new(&self.env, &self.token.address).mint(&account, &amount);
+        account
+    }
+}
+
+#[test]
+fn test_resale_returns_deposit_to_seller() {
+    let s = setup();
+    let seller = s.funded(100);
+    let buyer = s.funded(100);
+    let ticket_id = s.manager.purchase_ticket(&seller, &s.event_id, &0, &None, &None);
+    assert_eq!(s.token.balance(&seller), 50);
+
+    s.manager.resell_ticket(&seller, &buyer, &ticket_id, &0);
+    assert_eq!(s.token.balance(&seller), 100);
+    assert_eq!(s.token.balance(&buyer), 50);
+    assert_eq!(s.token.balance(&s.manager.address), 50);
+
+    // The buyer's deposit is held on the original terms and comes back to them
+    s.manager.check_in(&s.organizer, &ticket_id);
+    assert!(s.manager.try_claim_deposit(&seller, &ticket_id).is_err());
+    assert_eq!(s.manager.claim_deposit(&buyer, &ticket_id), 50);
+    assert_eq!(s.token.balance(&buyer), 100);
+}
+
+#[test]
+fn test_resale_needs_buyer_deposit() {
+    let s = setup();
+    let seller = s.funded(50);
+    let buyer = Address::generate(&s.env);
+    let ticket_id = s.manager.purchase_ticket(&seller, &s.event_id, &0, &None, &None);
+
+    assert!(s.manager.try_resell_ticket(&seller, &buyer, &ticket_id, &0).is_err());
+    assert_eq!(s.manager.get_ticket(&ticket_id).attendee, seller);
+}
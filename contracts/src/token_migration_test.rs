@@ -0,0 +1,68 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+
+struct Setup<'a> {
+    env: Env,
+    rewards: TokenRewardsClient<'a>,
+    admin: Address,
+}
+
+/// A contract whose storage was written by version 1: an admin pool for
+/// event 3 with 60 of its 100 tokens unclaimed, and a pool for event 9
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let rewards = TokenRewardsClient::new(&env, &env.register(TokenRewards, ()));
+    let text = String::from_str(&env, "Kaizen");
+    rewards.init(&admin, &text, &text, &7, &1_000_000, &10_000_000);
+
+    env.as_contract(&rewards.address, || {
+        let storage = env.storage().persistent();
+        for event_id in [3u64, 9] {
+            storage.set(&DataKey::RewardPool(event_id), &RewardPool {
+                funder: admin.clone(),
+                budget: 100,
+                remaining: 60,
+                per_attendee: 0,
+                deadline: 2_000,
+            });
+            storage.set(&DataKeyV1::EventRewards(event_id), &10i128);
+        }
+        storage.set(&DataKey::Balance(rewards.address.clone()), &120i128);
+        upgradeable::set_version(&env, 1);
+    });
+
+    Setup { env, rewards, admin }
+}
+
+#[test]
+fn test_migrated_pools_close_and_reclaim() {
+    let s = setup();
+    assert_eq!(s.rewards.migrate(&s.admin, &2, &3), 1);
+    assert_eq!(s.rewards.migrate(&s.admin, &2, &3), 1);
+    assert_eq!(s.rewards.migrate(&s.admin, &2, &3), STORAGE_VERSION);
+    assert_eq!(s.rewards.get_reward_pool(&3).per_attendee, 10);
+    assert_eq!(s.rewards.get_pool_contribution(&3, &s.admin), 100);
+
+    // Claims close at the pool deadline, after which the funder gets the remainder back
+    s.env.ledger().set_timestamp(2_001);
+    let before = s.rewards.balance(&s.admin);
+    assert_eq!(s.rewards.reclaim_reward_pool(&s.admin, &3), 60);
+    assert_eq!(s.rewards.balance(&s.admin), before + 60);
+}
+
+#[test]
+fn test_migration_covers_given_event_range() {
+    let s = setup();
+    assert_eq!(s.rewards.migrate(&s.admin, &100, &9), 1);
+    assert_eq!(s.rewards.migrate(&s.admin, &100, &9), STORAGE_VERSION);
+
+    // No attendance recorder knows these events; the range passed in is enough
+    for event_id in [3u64, 9] {
+        assert_eq!(s.rewards.get_reward_pool(&event_id).per_attendee, 10);
+        assert_eq!(s.rewards.get_pool_contribution(&event_id, &s.admin), 100);
+    }
+}
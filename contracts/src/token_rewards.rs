@@ -1,5 +1,19 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec, log};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, IntoVal, String, Symbol, Vec, BytesN, log};
+
+mod ownership;
+mod pausable;
+mod upgradeable;
+pub mod merkle;
+
+pub use pausable::PauseScope;
+
+#[cfg(test)]
+mod merkle_test;
+#[cfg(test)]
+mod reward_pool_test;
+#[cfg(test)]
+mod token_migration_test;
 
 #[derive(Clone)]
 #[contracttype]
@@ -8,6 +22,141 @@ pub struct RewardToken {
     pub symbol: String,
     pub decimals: u32,
     pub total_supply: i128,
+    pub max_supply: i128,
+}
+
+/// Reward amounts committed to as a Merkle root and claimed with proofs
+#[derive(Clone)]
+#[contracttype]
+pub struct MerkleDrop {
+    pub funder: Address,
+    pub root: BytesN<32>,
+    pub event_id: Option<u64>, // None for campaigns not tied to one event
+    pub total: i128,
+    pub claimed: i128,
+    pub expires_at: u64,       // funder may claw back the remainder after this
+    pub clawed_back: bool,
+}
+
+/// Claim progress of one event's rewards
+#[derive(Clone)]
+#[contracttype]
+pub struct RewardClaimStats {
+    pub claimed_count: u32,
+    pub claimed_total: i128,
+    pub unclaimed: i128,     // pool tokens still owed to claims; 0 once swept
+    pub claim_deadline: u64, // 0 = no deadline set
+}
+
+/// Result of one recipient in a batch distribution
+#[derive(Clone, PartialEq, Eq)]
+#[contracttype]
+pub enum DistributionOutcome {
+    Paid(i128),
+    AlreadyClaimed,
+    Ineligible, // non-positive amount, or no recorded attendance at the event
+}
+
+/// A balance or total supply as of the end of ledger `ledger`
+#[derive(Clone)]
+#[contracttype]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub value: i128,
+}
+
+/// Tokens set aside by a funder to pay one event's attendee rewards
+#[derive(Clone)]
+#[contracttype]
+pub struct RewardPool {
+    pub funder: Address,    // opened the pool: the admin or the event's organizer
+    pub budget: i128,       // total deposited
+    pub remaining: i128,    // not yet claimed or reclaimed
+    pub per_attendee: i128, // base reward per claim, before loyalty and streak bonuses
+    pub deadline: u64,      // funders may reclaim the remainder after this
+}
+
+/// Vesting terms applied to an event's rewards; times are ledger timestamps
+#[derive(Clone)]
+#[contracttype]
+pub struct VestingSchedule {
+    pub start: u64,
+    pub cliff: u64,    // seconds after `start` before anything vests
+    pub duration: u64, // seconds after `start` until fully vested
+}
+
+/// A single vesting reward held for a user
+#[derive(Clone)]
+#[contracttype]
+pub struct VestingGrant {
+    pub event_id: u64,
+    pub total: i128,
+    pub withdrawn: i128,
+    pub schedule: VestingSchedule,
+    pub revoked: bool,
+}
+
+/// Loyalty level reached after attending `min_events` events
+#[derive(Clone)]
+#[contracttype]
+pub struct LoyaltyTier {
+    pub name: Symbol,
+    pub min_events: u32,
+    pub multiplier_bps: u32, // applied to the base event reward, 10000 = 1x
+}
+
+/// A user's loyalty standing and progress to the next tier
+#[derive(Clone)]
+#[contracttype]
+pub struct LoyaltyStatus {
+    pub events_attended: u32,
+    pub tier: Option<Symbol>,
+    pub multiplier_bps: u32,
+    pub next_tier: Option<Symbol>,
+    pub events_to_next_tier: u32,
+}
+
+/// Position of an event within a recurring series
+#[derive(Clone)]
+#[contracttype]
+pub struct SeriesPosition {
+    pub series_id: u64,
+    pub sequence: u32,
+}
+
+/// Bonus on top of the base reward per consecutive attendance, capped at `max_bps`
+#[derive(Clone)]
+#[contracttype]
+pub struct StreakBonus {
+    pub step_bps: u32,
+    pub max_bps: u32,
+}
+
+/// A user's run of consecutive attendances in a series
+#[derive(Clone)]
+#[contracttype]
+pub struct Streak {
+    pub last_sequence: u32,
+    pub count: u32,
+}
+
+/// Thresholds for staking perk levels, the minimum lock and the unstaking cooldown
+#[derive(Clone)]
+#[contracttype]
+pub struct StakingConfig {
+    pub level_thresholds: Vec<i128>, // minimum stake for level 1, 2, ...
+    pub min_lock: u64,               // shortest `lock_period` a stake may use
+    pub cooldown: u64,               // seconds between unstake request and withdrawal
+}
+
+/// A user's staked tokens and any pending unstake
+#[derive(Clone)]
+#[contracttype]
+pub struct StakeInfo {
+    pub amount: i128,
+    pub locked_until: u64,
+    pub unstaking: i128,
+    pub unstake_available_at: u64,
 }
 
 #[derive(Clone)]
@@ -15,11 +164,64 @@ pub struct RewardToken {
 pub enum DataKey {
     TokenInfo,
     Balance(Address),
-    Admin,
-    EventRewards(u64),
     ClaimedRewards(Address, u64), // (user, event_id)
+    ClaimDeadline(u64),         // last timestamp an event's rewards can be claimed
+    ClaimCount(u64),
+    ClaimedTotal(u64),
+    RewardPool(u64),
+    PoolContribution(u64, Address), // (event_id, funder) -> deposits not yet reclaimed
+    PoolUnreclaimed(u64),       // sum of an event's `PoolContribution`s
+    VestingSchedule(u64),
+    VestingGrants(Address),
+    AttendanceRecorder,         // EventManager contract allowed to report check-ins
+    Attended(Address, u64),     // (user, event_id) -> streak at that event
+    AttendanceCount(Address),
+    LoyaltyTiers,
+    EventSeries(u64),
+    StreakBonus(u64),           // series_id
+    Streak(Address, u64),       // (user, series_id)
+    StakingConfig,
+    Stake(Address),
+    Allowance(Address, Address), // (owner, spender)
+    Escrowed,                   // tokens the contract holds for pools, vesting, stakes, drops and referrals
+    ReferralReward,             // paid per referred check-in
+    ReferralBudget,             // deposited tokens left for referral rewards
+    ReferralTotal(Address),     // lifetime referral rewards earned by a referrer
+    ReferralCredited(Address, Address), // (referrer, attendee) already rewarded
+    MerkleDropCounter,
+    MerkleDrop(u64),
+    MerkleClaimed(u64, u32),    // (drop_id, word) -> bitmap of claimed leaf indexes
+    BalanceCheckpoint(Address, u32), // (user, index), ascending by ledger
+    BalanceCheckpointCount(Address),
+    SupplyCheckpoint(u32),
+    SupplyCheckpointCount,
+    BalancesMigratedAt,         // ledger storage version 1 balances, which had no checkpoints, hold from
+    MigrationLastEvent,         // highest event ID a running migration searches for reward pools
+}
+
+/// `StakingConfig` as stored by storage version 1, before the minimum lock
+#[derive(Clone)]
+#[contracttype(export = false)]
+pub struct StakingConfigV1 {
+    pub level_thresholds: Vec<i128>,
+    pub cooldown: u64,
+}
+
+/// Storage keys of version 1 that were dropped since
+#[derive(Clone)]
+#[contracttype(export = false)]
+pub enum DataKeyV1 {
+    EventRewards(u64), // per-attendee reward, now kept on the event's pool
 }
 
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Leaf indexes tracked per `MerkleClaimed` bitmap word
+const CLAIM_WORD_BITS: u32 = 128;
+
+/// Storage layout version written by this code
+const STORAGE_VERSION: u32 = 2;
+
 #[contract]
 pub struct TokenRewards;
 
@@ -33,53 +235,77 @@ impl TokenRewards {
         symbol: String,
         decimals: u32,
         total_supply: i128,
+        max_supply: i128,
     ) {
+        // Admin must authorize; panics if already initialized
+        ownership::init(&env, &admin);
+        upgradeable::set_version(&env, STORAGE_VERSION);
+
+        if total_supply < 0 || total_supply > max_supply {
+            panic!("Initial supply must be between 0 and max supply");
+        }
+
         let token = RewardToken {
             name,
             symbol,
             decimals,
             total_supply,
+            max_supply,
         };
 
-        env.storage().persistent().set(&DataKey::TokenInfo, &token);
-        env.storage().persistent().set(&DataKey::Admin, &admin);
-        
+        Self::write_token_info(&env, &token);
+
         // Give all initial supply to admin
-        env.storage().persistent().set(&DataKey::Balance(admin.clone()), &total_supply);
+        Self::write_balance(&env, &admin, total_supply);
 
         log!(&env, "Token Rewards initialized: {} ({})", token.name, token.symbol);
     }
 
-    /// Set reward amount for an event
+    /// Change the reward per attendee and claim deadline of an event's pool (admin only)
     pub fn set_event_reward(
         env: Env,
         admin: Address,
         event_id: u64,
         reward_amount: i128,
+        claim_deadline: u64,
     ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
         admin.require_auth();
 
-        let stored_admin: Address = env.storage()
-            .persistent()
-            .get(&DataKey::Admin)
-            .expect("Admin not set");
-
-        if stored_admin != admin {
+        if !ownership::is_owner(&env, &admin) {
             panic!("Only admin can set event rewards");
         }
 
-        env.storage().persistent().set(&DataKey::EventRewards(event_id), &reward_amount);
+        if claim_deadline <= env.ledger().timestamp() {
+            panic!("Claim deadline must be in the future");
+        }
+
+        let mut pool = Self::get_reward_pool(env.clone(), event_id);
+        pool.per_attendee = reward_amount;
+        env.storage().persistent().set(&DataKey::RewardPool(event_id), &pool);
+        env.storage().persistent().set(&DataKey::ClaimDeadline(event_id), &claim_deadline);
         
-        log!(&env, "Event reward set: {} tokens for event {}", reward_amount, event_id);
+        log!(&env, "Event reward set: {} tokens for event {} until {}", reward_amount, event_id, claim_deadline);
     }
 
-    /// Claim tokens for attending an event
+    /// Claim tokens for attending an event. Only users with a check-in recorded by
+    /// the attendance recorder can claim; without one, the admin distributes rewards.
     pub fn claim_event_reward(
         env: Env,
         user: Address,
         event_id: u64,
     ) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
         user.require_auth();
+        Self::require_claim_window(&env, event_id);
+
+        if !env.storage().persistent().has(&DataKey::AttendanceRecorder) {
+            panic!("Attendance recorder not set");
+        }
+        if !env.storage().persistent().has(&DataKey::Attended(user.clone(), event_id)) {
+            panic!("No recorded attendance at this event");
+        }
 
         // Check if already claimed
         let claim_key = DataKey::ClaimedRewards(user.clone(), event_id);
@@ -87,45 +313,26 @@ impl TokenRewards {
             panic!("Reward already claimed for this event");
         }
 
-        // Get reward amount for event
-        let reward_amount: i128 = env.storage()
-            .persistent()
-            .get(&DataKey::EventRewards(event_id))
-            .unwrap_or(0);
-
-        if reward_amount == 0 {
+        let mut pool = Self::get_reward_pool(env.clone(), event_id);
+        if pool.per_attendee <= 0 {
             panic!("No reward set for this event");
         }
 
-        // Get admin balance (contract's token pool)
-        let admin: Address = env.storage()
-            .persistent()
-            .get(&DataKey::Admin)
-            .expect("Admin not set");
-        
-        let admin_balance: i128 = env.storage()
-            .persistent()
-            .get(&DataKey::Balance(admin.clone()))
-            .unwrap_or(0);
+        // Apply the user's loyalty multiplier and streak bonus
+        let reward_amount = Self::reward_for(&env, &user, event_id, pool.per_attendee);
 
-        if admin_balance < reward_amount {
+        // Pay from the event's reward pool
+        if pool.remaining < reward_amount {
             panic!("Insufficient tokens in reward pool");
         }
 
-        // Transfer tokens from admin to user
-        let new_admin_balance = admin_balance - reward_amount;
-        let user_balance: i128 = env.storage()
-            .persistent()
-            .get(&DataKey::Balance(user.clone()))
-            .unwrap_or(0);
-        let new_user_balance = user_balance + reward_amount;
-
-        // Update balances
-        env.storage().persistent().set(&DataKey::Balance(admin), &new_admin_balance);
-        env.storage().persistent().set(&DataKey::Balance(user.clone()), &new_user_balance);
+        pool.remaining -= reward_amount;
+        env.storage().persistent().set(&DataKey::RewardPool(event_id), &pool);
+        Self::pay_reward(&env, event_id, &user, reward_amount);
 
         // Mark as claimed
         env.storage().persistent().set(&claim_key, &true);
+        Self::record_claim(&env, event_id, reward_amount);
 
         log!(&env, "Reward claimed: {} tokens by {} for event {}", reward_amount, user, event_id);
         reward_amount
@@ -138,171 +345,1589 @@ impl TokenRewards {
         event_id: u64,
         recipients: Vec<Address>,
     ) -> Vec<i128> {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
         admin.require_auth();
 
-        let stored_admin: Address = env.storage()
-            .persistent()
-            .get(&DataKey::Admin)
-            .expect("Admin not set");
-
-        if stored_admin != admin {
+        if !ownership::is_owner(&env, &admin) {
             panic!("Only admin can batch distribute rewards");
         }
+        Self::require_claim_window(&env, event_id);
 
-        let reward_amount: i128 = env.storage()
-            .persistent()
-            .get(&DataKey::EventRewards(event_id))
-            .unwrap_or(0);
-
-        if reward_amount == 0 {
+        let mut pool = Self::get_reward_pool(env.clone(), event_id);
+        let reward_amount = pool.per_attendee;
+        if reward_amount <= 0 {
             panic!("No reward set for this event");
         }
 
-        let total_reward = reward_amount * (recipients.len() as i128);
-        let admin_balance: i128 = env.storage()
-            .persistent()
-            .get(&DataKey::Balance(admin.clone()))
-            .unwrap_or(0);
-
-        if admin_balance < total_reward {
-            panic!("Insufficient tokens in reward pool");
-        }
-
         let mut distributed_amounts: Vec<i128> = Vec::new(&env);
-        let mut new_admin_balance = admin_balance;
 
         for recipient in recipients.iter() {
             let claim_key = DataKey::ClaimedRewards(recipient.clone(), event_id);
             
             if !env.storage().persistent().has(&claim_key) {
-                let user_balance: i128 = env.storage()
-                    .persistent()
-                    .get(&DataKey::Balance(recipient.clone()))
-                    .unwrap_or(0);
-                
-                let new_user_balance = user_balance + reward_amount;
-                new_admin_balance -= reward_amount;
+                let amount = Self::reward_for(&env, &recipient, event_id, reward_amount);
+                if pool.remaining < amount {
+                    panic!("Insufficient tokens in reward pool");
+                }
 
-                env.storage().persistent().set(&DataKey::Balance(recipient.clone()), &new_user_balance);
+                pool.remaining -= amount;
+                Self::pay_reward(&env, event_id, &recipient, amount);
                 env.storage().persistent().set(&claim_key, &true);
+                Self::record_claim(&env, event_id, amount);
 
-                distributed_amounts.push_back(reward_amount);
+                distributed_amounts.push_back(amount);
             } else {
                 distributed_amounts.push_back(0); // Already claimed
             }
         }
 
-        env.storage().persistent().set(&DataKey::Balance(admin), &new_admin_balance);
+        env.storage().persistent().set(&DataKey::RewardPool(event_id), &pool);
 
         log!(&env, "Batch distributed rewards for event {}: {} recipients", event_id, recipients.len());
         distributed_amounts
     }
 
-    /// Get token balance
-    pub fn balance(env: Env, user: Address) -> i128 {
-        env.storage()
-            .persistent()
-            .get(&DataKey::Balance(user))
-            .unwrap_or(0)
-    }
+    /// Pay each recipient its own amount from the event's reward pool (admin only).
+    /// Recipients who already claimed, or are ineligible, are skipped rather than
+    /// failing the batch; the pool only needs to cover what is actually paid.
+    pub fn batch_distribute_amounts(
+        env: Env,
+        admin: Address,
+        event_id: u64,
+        payouts: Vec<(Address, i128)>,
+    ) -> Vec<DistributionOutcome> {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+        admin.require_auth();
 
-    /// Get token info
-    pub fn token_info(env: Env) -> RewardToken {
-        env.storage()
-            .persistent()
-            .get(&DataKey::TokenInfo)
-            .expect("Token not initialized")
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can batch distribute rewards");
+        }
+        Self::require_claim_window(&env, event_id);
+
+        let mut pool = Self::get_reward_pool(env.clone(), event_id);
+        // Without a recorder there is no attendance to check against
+        let check_attendance = env.storage().persistent().has(&DataKey::AttendanceRecorder);
+
+        let mut outcomes: Vec<DistributionOutcome> = Vec::new(&env);
+        let mut total_paid: i128 = 0;
+        let (mut paid, mut already_claimed, mut ineligible) = (0u32, 0u32, 0u32);
+
+        for (recipient, amount) in payouts.iter() {
+            let claim_key = DataKey::ClaimedRewards(recipient.clone(), event_id);
+            let attended = env.storage().persistent().has(&DataKey::Attended(recipient.clone(), event_id));
+
+            let outcome = if env.storage().persistent().has(&claim_key) {
+                already_claimed += 1;
+                DistributionOutcome::AlreadyClaimed
+            } else if amount <= 0 || (check_attendance && !attended) {
+                ineligible += 1;
+                DistributionOutcome::Ineligible
+            } else {
+                // Panicking below reverts these payments too
+                Self::pay_reward(&env, event_id, &recipient, amount);
+                env.storage().persistent().set(&claim_key, &true);
+                Self::record_claim(&env, event_id, amount);
+                total_paid += amount;
+                paid += 1;
+                DistributionOutcome::Paid(amount)
+            };
+            outcomes.push_back(outcome);
+        }
+
+        if pool.remaining < total_paid {
+            panic!("Insufficient tokens in reward pool");
+        }
+
+        pool.remaining -= total_paid;
+        env.storage().persistent().set(&DataKey::RewardPool(event_id), &pool);
+
+        env.events().publish(
+            (Symbol::new(&env, "rewards"), Symbol::new(&env, "batch_paid")),
+            (event_id, paid, already_claimed, ineligible, total_paid),
+        );
+
+        outcomes
     }
 
-    /// Transfer tokens between users
-    pub fn transfer(
+    /// Deposit tokens into an event's reward pool.
+    /// The first deposit opens the pool and must come from the admin or the event's
+    /// organizer. It sets the per-attendee reward and the deadline, which is both the
+    /// claim deadline and when funders may reclaim the remainder. Anyone, such as a
+    /// sponsor, may top up the budget until claims close.
+    pub fn fund_reward_pool(
         env: Env,
-        from: Address,
-        to: Address,
+        funder: Address,
+        event_id: u64,
         amount: i128,
+        per_attendee: i128,
+        deadline: u64,
     ) {
-        from.require_auth();
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        funder.require_auth();
 
         if amount <= 0 {
-            panic!("Transfer amount must be positive");
+            panic!("Deposit amount must be positive");
         }
 
-        let from_balance: i128 = env.storage()
-            .persistent()
-            .get(&DataKey::Balance(from.clone()))
-            .unwrap_or(0);
+        let key = DataKey::RewardPool(event_id);
+        let pool = match env.storage().persistent().get::<_, RewardPool>(&key) {
+            Some(mut pool) => {
+                if Self::claims_closed(&env, event_id) {
+                    panic!("Reward pool is closed");
+                }
+                pool.budget += amount;
+                pool.remaining += amount;
+                pool
+            }
+            None => {
+                if !Self::can_open_pool(&env, &funder, event_id) {
+                    panic!("Only admin or the event organizer can open a reward pool");
+                }
+                if per_attendee <= 0 {
+                    panic!("Reward per attendee must be positive");
+                }
+                if deadline <= env.ledger().timestamp() {
+                    panic!("Reward pool deadline must be in the future");
+                }
+                env.storage().persistent().set(&DataKey::ClaimDeadline(event_id), &deadline);
+                RewardPool {
+                    funder: funder.clone(),
+                    budget: amount,
+                    remaining: amount,
+                    per_attendee,
+                    deadline,
+                }
+            }
+        };
 
-        if from_balance < amount {
-            panic!("Insufficient balance");
+        // Escrow the deposit under the contract's own balance
+        Self::escrow_in(&env, &funder, amount);
+        env.storage().persistent().set(&key, &pool);
+        Self::add_contribution(&env, event_id, &funder, amount);
+
+        log!(&env, "Reward pool funded: {} tokens for event {}", amount, event_id);
+    }
+
+    /// Return a funder's share of a pool's unclaimed tokens once claims have closed
+    pub fn reclaim_reward_pool(env: Env, funder: Address, event_id: u64) -> i128 {
+        Self::sweep_expired_rewards(env, funder, event_id, None)
+    }
+
+    /// Sweep a funder's share of a pool's unclaimed tokens once its deadline and
+    /// claim window have passed: into the open pool of `into_event`, or back to the
+    /// funder. Each funder's share is proportional to what they deposited.
+    pub fn sweep_expired_rewards(
+        env: Env,
+        funder: Address,
+        event_id: u64,
+        into_event: Option<u64>,
+    ) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+        funder.require_auth();
+
+        let mut pool = Self::get_reward_pool(env.clone(), event_id);
+        if env.ledger().timestamp() <= pool.deadline || !Self::claims_closed(&env, event_id) {
+            panic!("Reward pool deadline has not passed");
         }
 
-        let to_balance: i128 = env.storage()
-            .persistent()
-            .get(&DataKey::Balance(to.clone()))
-            .unwrap_or(0);
+        let contribution_key = DataKey::PoolContribution(event_id, funder.clone());
+        let contribution: i128 = env.storage().persistent().get(&contribution_key).unwrap_or(0);
+        if contribution <= 0 {
+            panic!("Only a pool funder can reclaim");
+        }
 
-        let new_from_balance = from_balance - amount;
-        let new_to_balance = to_balance + amount;
+        // Shares of the remainder stay proportional as funders reclaim one by one;
+        // the last funder out takes any rounding dust
+        let unreclaimed_key = DataKey::PoolUnreclaimed(event_id);
+        let unreclaimed: i128 = env.storage().persistent().get(&unreclaimed_key).unwrap_or(0);
+        let amount = pool.remaining * contribution / unreclaimed;
+        if amount <= 0 {
+            panic!("Nothing left to reclaim");
+        }
 
-        env.storage().persistent().set(&DataKey::Balance(from.clone()), &new_from_balance);
-        env.storage().persistent().set(&DataKey::Balance(to.clone()), &new_to_balance);
+        env.storage().persistent().remove(&contribution_key);
+        env.storage().persistent().set(&unreclaimed_key, &(unreclaimed - contribution));
+        pool.remaining -= amount;
+        env.storage().persistent().set(&DataKey::RewardPool(event_id), &pool);
 
-        log!(&env, "Transferred {} tokens from {} to {}", amount, from, to);
+        match into_event {
+            Some(target_id) => {
+                let mut target = Self::get_reward_pool(env.clone(), target_id);
+                if target_id == event_id || Self::claims_closed(&env, target_id) {
+                    panic!("Target reward pool is closed");
+                }
+
+                // Tokens stay escrowed in the contract
+                target.budget += amount;
+                target.remaining += amount;
+                env.storage().persistent().set(&DataKey::RewardPool(target_id), &target);
+                Self::add_contribution(&env, target_id, &funder, amount);
+
+                log!(&env, "Expired rewards swept: {} tokens from event {} to event {}", amount, event_id, target_id);
+            }
+            None => {
+                Self::escrow_out(&env, &funder, amount);
+
+                log!(&env, "Reward pool reclaimed: {} tokens for event {}", amount, event_id);
+            }
+        }
+        amount
     }
 
-    /// Check if user has claimed reward for event
-    pub fn has_claimed_reward(env: Env, user: Address, event_id: u64) -> bool {
-        let claim_key = DataKey::ClaimedRewards(user, event_id);
-        env.storage().persistent().has(&claim_key)
+    /// Get an event's reward pool
+    pub fn get_reward_pool(env: Env, event_id: u64) -> RewardPool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RewardPool(event_id))
+            .expect("No reward pool for this event")
     }
 
-    /// Get event reward amount
-    pub fn get_event_reward(env: Env, event_id: u64) -> i128 {
+    /// Get what a funder has deposited into an event's pool and not yet reclaimed
+    pub fn get_pool_contribution(env: Env, event_id: u64, funder: Address) -> i128 {
         env.storage()
             .persistent()
-            .get(&DataKey::EventRewards(event_id))
+            .get(&DataKey::PoolContribution(event_id, funder))
             .unwrap_or(0)
     }
 
-    /// Mint additional tokens (admin only)
-    pub fn mint(env: Env, admin: Address, amount: i128) {
+    /// Publish a Merkle root of `(index, address, amount)` entries and escrow `total`
+    /// from the admin's balance. Roots and proofs come from the `merkle` module.
+    pub fn create_merkle_drop(
+        env: Env,
+        admin: Address,
+        root: BytesN<32>,
+        total: i128,
+        expires_at: u64,
+        event_id: Option<u64>,
+    ) -> u64 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
         admin.require_auth();
 
-        let stored_admin: Address = env.storage()
-            .persistent()
-            .get(&DataKey::Admin)
-            .expect("Admin not set");
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can create Merkle drops");
+        }
 
-        if stored_admin != admin {
-            panic!("Only admin can mint tokens");
+        if total <= 0 {
+            panic!("Drop total must be positive");
         }
 
-        let admin_balance: i128 = env.storage()
-            .persistent()
-            .get(&DataKey::Balance(admin.clone()))
-            .unwrap_or(0);
-        
-        let new_admin_balance = admin_balance + amount;
+        if expires_at <= env.ledger().timestamp() {
+            panic!("Drop expiry must be in the future");
+        }
 
-        // Update total supply
-        let mut token_info: RewardToken = env.storage()
+        let drop_id: u64 = env.storage()
             .persistent()
-            .get(&DataKey::TokenInfo)
-            .expect("Token not initialized");
-        
-        token_info.total_supply += amount;
+            .get(&DataKey::MerkleDropCounter)
+            .unwrap_or(0)
+            + 1;
 
-        env.storage().persistent().set(&DataKey::Balance(admin), &new_admin_balance);
-        env.storage().persistent().set(&DataKey::TokenInfo, &token_info);
+        let drop = MerkleDrop {
+            funder: admin.clone(),
+            root,
+            event_id,
+            total,
+            claimed: 0,
+            expires_at,
+            clawed_back: false,
+        };
 
-        log!(&env, "Minted {} tokens to admin", amount);
+        Self::escrow_in(&env, &admin, total);
+        env.storage().persistent().set(&DataKey::MerkleDrop(drop_id), &drop);
+        env.storage().persistent().set(&DataKey::MerkleDropCounter, &drop_id);
+
+        log!(&env, "Merkle drop {} created: {} tokens", drop_id, total);
+        drop_id
     }
 
-    /// Get current admin
-    pub fn get_admin(env: Env) -> Address {
+    /// Claim the entry at `index` of a Merkle drop with its proof
+    pub fn claim_merkle_drop(
+        env: Env,
+        claimant: Address,
+        drop_id: u64,
+        index: u32,
+        amount: i128,
+        proof: Vec<BytesN<32>>,
+    ) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+        claimant.require_auth();
+
+        let mut drop = Self::get_merkle_drop(env.clone(), drop_id);
+        if drop.clawed_back || env.ledger().timestamp() >= drop.expires_at {
+            panic!("Merkle drop has expired");
+        }
+
+        if amount <= 0 {
+            panic!("Claim amount must be positive");
+        }
+
+        let word_key = DataKey::MerkleClaimed(drop_id, index / CLAIM_WORD_BITS);
+        let bit = 1u128 << (index % CLAIM_WORD_BITS);
+        let word: u128 = env.storage().persistent().get(&word_key).unwrap_or(0);
+        if word & bit != 0 {
+            panic!("Reward already claimed");
+        }
+
+        let leaf = merkle::leaf(&env, index, &claimant, amount);
+        if !merkle::verify(&env, &drop.root, &leaf, &proof) {
+            panic!("Invalid Merkle proof");
+        }
+
+        if drop.claimed + amount > drop.total {
+            panic!("Merkle drop exhausted");
+        }
+
+        drop.claimed += amount;
+        env.storage().persistent().set(&word_key, &(word | bit));
+        env.storage().persistent().set(&DataKey::MerkleDrop(drop_id), &drop);
+        Self::escrow_out(&env, &claimant, amount);
+
+        log!(&env, "Merkle drop {} claimed: {} tokens to {}", drop_id, amount, claimant);
+        amount
+    }
+
+    /// Return a drop's unclaimed tokens to its funder once it has expired
+    pub fn clawback_merkle_drop(env: Env, funder: Address, drop_id: u64) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+        funder.require_auth();
+
+        let mut drop = Self::get_merkle_drop(env.clone(), drop_id);
+        if drop.funder != funder {
+            panic!("Only the drop funder can claw back");
+        }
+
+        if env.ledger().timestamp() < drop.expires_at {
+            panic!("Merkle drop has not expired");
+        }
+
+        if drop.clawed_back {
+            panic!("Merkle drop already clawed back");
+        }
+
+        let amount = drop.total - drop.claimed;
+        drop.clawed_back = true;
+        env.storage().persistent().set(&DataKey::MerkleDrop(drop_id), &drop);
+        if amount > 0 {
+            Self::escrow_out(&env, &funder, amount);
+        }
+
+        log!(&env, "Merkle drop {} clawed back: {} tokens", drop_id, amount);
+        amount
+    }
+
+    /// Get a Merkle drop
+    pub fn get_merkle_drop(env: Env, drop_id: u64) -> MerkleDrop {
         env.storage()
             .persistent()
-            .get(&DataKey::Admin)
-            .expect("Admin not set")
+            .get(&DataKey::MerkleDrop(drop_id))
+            .expect("Merkle drop not found")
+    }
+
+    /// Check whether the entry at `index` of a drop has been claimed
+    pub fn is_merkle_claimed(env: Env, drop_id: u64, index: u32) -> bool {
+        let word: u128 = env.storage()
+            .persistent()
+            .get(&DataKey::MerkleClaimed(drop_id, index / CLAIM_WORD_BITS))
+            .unwrap_or(0);
+        word & (1u128 << (index % CLAIM_WORD_BITS)) != 0
+    }
+
+    /// Make an event's rewards vest over time instead of paying out on claim (admin only)
+    pub fn set_vesting_schedule(
+        env: Env,
+        admin: Address,
+        event_id: u64,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can set vesting schedules");
+        }
+
+        if duration == 0 || cliff > duration {
+            panic!("Invalid vesting schedule");
+        }
+
+        let schedule = VestingSchedule { start, cliff, duration };
+        env.storage().persistent().set(&DataKey::VestingSchedule(event_id), &schedule);
+
+        log!(&env, "Vesting schedule set for event {}: {} over {}s", event_id, start, duration);
+    }
+
+    /// Get the amount a user can withdraw from their vesting rewards right now
+    pub fn claimable(env: Env, user: Address) -> i128 {
+        let now = env.ledger().timestamp();
+        let mut total: i128 = 0;
+        for grant in Self::get_vesting_grants(env.clone(), user).iter() {
+            total += Self::vested_amount(&grant, now) - grant.withdrawn;
+        }
+        total
+    }
+
+    /// Withdraw all vested rewards to the user's balance
+    pub fn withdraw_vested(env: Env, user: Address) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Claims);
+        user.require_auth();
+
+        let now = env.ledger().timestamp();
+        let grants = Self::get_vesting_grants(env.clone(), user.clone());
+        let mut updated: Vec<VestingGrant> = Vec::new(&env);
+        let mut amount: i128 = 0;
+
+        for mut grant in grants.iter() {
+            let vested = Self::vested_amount(&grant, now);
+            amount += vested - grant.withdrawn;
+            grant.withdrawn = vested;
+
+            // Drop grants that are fully paid out
+            if grant.withdrawn < grant.total {
+                updated.push_back(grant);
+            }
+        }
+
+        if amount <= 0 {
+            panic!("Nothing vested to withdraw");
+        }
+
+        env.storage().persistent().set(&DataKey::VestingGrants(user.clone()), &updated);
+        Self::escrow_out(&env, &user, amount);
+
+        log!(&env, "Vested rewards withdrawn: {} tokens by {}", amount, user);
+        amount
+    }
+
+    /// Revoke the unvested part of a user's reward for an event, returning it to
+    /// the event's pool (admin only). Already-vested tokens stay withdrawable.
+    pub fn revoke_vesting(env: Env, admin: Address, user: Address, event_id: u64) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can revoke vesting");
+        }
+
+        let now = env.ledger().timestamp();
+        let mut grants = Self::get_vesting_grants(env.clone(), user.clone());
+        let mut unvested: i128 = 0;
+
+        for i in 0..grants.len() {
+            let mut grant = grants.get(i).unwrap();
+            if grant.event_id != event_id || grant.revoked {
+                continue;
+            }
+
+            let vested = Self::vested_amount(&grant, now);
+            unvested = grant.total - vested;
+            grant.total = vested;
+            grant.revoked = true;
+            if grant.withdrawn < grant.total {
+                grants.set(i, grant);
+            } else {
+                grants.remove(i);
+            }
+            break;
+        }
+
+        if unvested <= 0 {
+            panic!("No unvested reward to revoke");
+        }
+
+        let mut pool = Self::get_reward_pool(env.clone(), event_id);
+        pool.remaining += unvested;
+        env.storage().persistent().set(&DataKey::RewardPool(event_id), &pool);
+        env.storage().persistent().set(&DataKey::VestingGrants(user.clone()), &grants);
+
+        log!(&env, "Vesting revoked: {} tokens from {} for event {}", unvested, user, event_id);
+        unvested
+    }
+
+    /// Get a user's outstanding vesting grants
+    pub fn get_vesting_grants(env: Env, user: Address) -> Vec<VestingGrant> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::VestingGrants(user))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Set the contract allowed to report check-ins, normally EventManager (admin only)
+    pub fn set_attendance_recorder(env: Env, admin: Address, recorder: Address) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can set the attendance recorder");
+        }
+
+        env.storage().persistent().set(&DataKey::AttendanceRecorder, &recorder);
+
+        log!(&env, "Attendance recorder set: {}", recorder);
+    }
+
+    /// Record a verified check-in (attendance recorder only)
+    pub fn record_attendance(env: Env, user: Address, event_id: u64) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        let recorder: Address = env.storage()
+            .persistent()
+            .get(&DataKey::AttendanceRecorder)
+            .expect("Attendance recorder not set");
+        recorder.require_auth();
+
+        let attended_key = DataKey::Attended(user.clone(), event_id);
+        if env.storage().persistent().has(&attended_key) {
+            return;
+        }
+
+        let count = Self::get_attendance_count(env.clone(), user.clone()) + 1;
+        let streak = Self::advance_streak(&env, &user, event_id);
+        env.storage().persistent().set(&attended_key, &streak);
+        env.storage().persistent().set(&DataKey::AttendanceCount(user.clone()), &count);
+
+        log!(&env, "Attendance recorded: {} at event {} ({} total)", user, event_id, count);
+    }
+
+    /// Set loyalty tiers, ordered by ascending `min_events` (admin only)
+    pub fn set_loyalty_tiers(env: Env, admin: Address, tiers: Vec<LoyaltyTier>) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can set loyalty tiers");
+        }
+
+        let mut previous: Option<u32> = None;
+        for tier in tiers.iter() {
+            if previous.is_some_and(|min| tier.min_events <= min) {
+                panic!("Loyalty tiers must have ascending thresholds");
+            }
+            if tier.multiplier_bps < BPS_DENOMINATOR {
+                panic!("Loyalty multiplier cannot be below 1x");
+            }
+            previous = Some(tier.min_events);
+        }
+
+        env.storage().persistent().set(&DataKey::LoyaltyTiers, &tiers);
+
+        log!(&env, "Loyalty tiers set: {} tiers", tiers.len());
+    }
+
+    /// Get configured loyalty tiers
+    pub fn get_loyalty_tiers(env: Env) -> Vec<LoyaltyTier> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::LoyaltyTiers)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get number of verified events a user has attended
+    pub fn get_attendance_count(env: Env, user: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AttendanceCount(user))
+            .unwrap_or(0)
+    }
+
+    /// Get a user's loyalty tier and progress to the next one
+    pub fn get_loyalty_status(env: Env, user: Address) -> LoyaltyStatus {
+        let events_attended = Self::get_attendance_count(env.clone(), user);
+        let mut status = LoyaltyStatus {
+            events_attended,
+            tier: None,
+            multiplier_bps: BPS_DENOMINATOR,
+            next_tier: None,
+            events_to_next_tier: 0,
+        };
+
+        for tier in Self::get_loyalty_tiers(env).iter() {
+            if events_attended >= tier.min_events {
+                status.tier = Some(tier.name);
+                status.multiplier_bps = tier.multiplier_bps;
+            } else {
+                status.events_to_next_tier = tier.min_events - events_attended;
+                status.next_tier = Some(tier.name);
+                break;
+            }
+        }
+
+        status
+    }
+
+    /// Set the reward credited to a referrer for each referred check-in (admin only)
+    pub fn set_referral_reward(env: Env, admin: Address, reward: i128) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can set the referral reward");
+        }
+
+        if reward < 0 {
+            panic!("Referral reward cannot be negative");
+        }
+
+        env.storage().persistent().set(&DataKey::ReferralReward, &reward);
+
+        log!(&env, "Referral reward set: {}", reward);
+    }
+
+    /// Deposit tokens to pay referral rewards from
+    pub fn fund_referrals(env: Env, funder: Address, amount: i128) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        funder.require_auth();
+
+        if amount <= 0 {
+            panic!("Deposit amount must be positive");
+        }
+
+        Self::escrow_in(&env, &funder, amount);
+        let budget = Self::get_referral_budget(env.clone()) + amount;
+        env.storage().persistent().set(&DataKey::ReferralBudget, &budget);
+
+        log!(&env, "Referral budget funded by {}: {}", funder, amount);
+    }
+
+    /// Credit `referrer` for bringing `attendee` to an event (attendance recorder only).
+    /// Each referred attendee earns its referrer one reward. Pays nothing on
+    /// self-referral, for a repeat attendee or once the referral budget runs out.
+    /// Panics while paused; EventManager calls it fallibly, so check-ins still
+    /// succeed. Returns the amount credited.
+    pub fn credit_referral(env: Env, referrer: Address, attendee: Address, event_id: u64) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        let recorder: Address = env.storage()
+            .persistent()
+            .get(&DataKey::AttendanceRecorder)
+            .expect("Attendance recorder not set");
+        recorder.require_auth();
+
+        let credited_key = DataKey::ReferralCredited(referrer.clone(), attendee.clone());
+        let reward = Self::get_referral_reward(env.clone());
+        let budget = Self::get_referral_budget(env.clone());
+        if referrer == attendee
+            || reward == 0
+            || budget < reward
+            || env.storage().persistent().has(&credited_key)
+        {
+            return 0;
+        }
+
+        env.storage().persistent().set(&credited_key, &true);
+
+        Self::escrow_out(&env, &referrer, reward);
+        env.storage().persistent().set(&DataKey::ReferralBudget, &(budget - reward));
+
+        let total = Self::get_referral_total(env.clone(), referrer.clone()) + reward;
+        env.storage().persistent().set(&DataKey::ReferralTotal(referrer.clone()), &total);
+
+        log!(&env, "Referral reward {} to {} for {} at event {}", reward, referrer, attendee, event_id);
+        reward
+    }
+
+    /// Get the reward per referred check-in
+    pub fn get_referral_reward(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReferralReward)
+            .unwrap_or(0)
+    }
+
+    /// Get tokens left for referral rewards
+    pub fn get_referral_budget(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReferralBudget)
+            .unwrap_or(0)
+    }
+
+    /// Get total referral rewards a referrer has earned
+    pub fn get_referral_total(env: Env, referrer: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReferralTotal(referrer))
+            .unwrap_or(0)
+    }
+
+    /// Place an event in a series; consecutive `sequence` numbers build streaks (admin only)
+    pub fn set_event_series(
+        env: Env,
+        admin: Address,
+        event_id: u64,
+        series_id: u64,
+        sequence: u32,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can set event series");
+        }
+
+        let position = SeriesPosition { series_id, sequence };
+        env.storage().persistent().set(&DataKey::EventSeries(event_id), &position);
+
+        log!(&env, "Event {} set as #{} in series {}", event_id, sequence, series_id);
+    }
+
+    /// Set the streak bonus schedule for a series (admin only). Each consecutive
+    /// attendance after the first adds `step_bps` of the base reward, up to `max_bps`.
+    pub fn set_streak_bonus(
+        env: Env,
+        admin: Address,
+        series_id: u64,
+        step_bps: u32,
+        max_bps: u32,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can set streak bonuses");
+        }
+
+        if step_bps > BPS_DENOMINATOR || max_bps > BPS_DENOMINATOR {
+            panic!("Streak bonus cannot exceed 10000 bps");
+        }
+
+        let bonus = StreakBonus { step_bps, max_bps };
+        env.storage().persistent().set(&DataKey::StreakBonus(series_id), &bonus);
+
+        log!(&env, "Streak bonus set for series {}: {} bps per step", series_id, step_bps);
+    }
+
+    /// Get a user's current streak in a series
+    pub fn get_streak(env: Env, user: Address, series_id: u64) -> Streak {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Streak(user, series_id))
+            .unwrap_or(Streak {
+                last_sequence: 0,
+                count: 0,
+            })
+    }
+
+    /// Set perk level thresholds, the minimum lock and the unstaking cooldown (admin only)
+    pub fn set_staking_config(
+        env: Env,
+        admin: Address,
+        level_thresholds: Vec<i128>,
+        min_lock: u64,
+        cooldown: u64,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can configure staking");
+        }
+
+        let mut previous: i128 = 0;
+        for threshold in level_thresholds.iter() {
+            if threshold <= previous {
+                panic!("Perk thresholds must be positive and ascending");
+            }
+            previous = threshold;
+        }
+
+        let config = StakingConfig { level_thresholds, min_lock, cooldown };
+        env.storage().persistent().set(&DataKey::StakingConfig, &config);
+
+        log!(&env, "Staking configured: {} perk levels", config.level_thresholds.len());
+    }
+
+    /// Lock tokens for at least `lock_period` seconds to earn perk levels
+    pub fn stake(env: Env, user: Address, amount: i128, lock_period: u64) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        user.require_auth();
+
+        if amount <= 0 {
+            panic!("Stake amount must be positive");
+        }
+
+        let min_lock = env.storage()
+            .persistent()
+            .get::<_, StakingConfig>(&DataKey::StakingConfig)
+            .map(|config| config.min_lock)
+            .unwrap_or(0);
+        if lock_period < min_lock {
+            panic!("Lock period is below the minimum");
+        }
+
+        let mut stake = Self::get_stake(env.clone(), user.clone());
+        stake.amount += amount;
+        stake.locked_until = stake.locked_until.max(env.ledger().timestamp() + lock_period);
+
+        Self::escrow_in(&env, &user, amount);
+        env.storage().persistent().set(&DataKey::Stake(user.clone()), &stake);
+
+        log!(&env, "Staked {} tokens by {} until {}", amount, user, stake.locked_until);
+    }
+
+    /// Start unstaking once the lock has expired; tokens stop counting for perks
+    /// immediately and can be withdrawn after the cooldown
+    pub fn request_unstake(env: Env, user: Address, amount: i128) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        user.require_auth();
+
+        let mut stake = Self::get_stake(env.clone(), user.clone());
+        if amount <= 0 || amount > stake.amount {
+            panic!("Invalid unstake amount");
+        }
+
+        let now = env.ledger().timestamp();
+        if now < stake.locked_until {
+            panic!("Stake is still locked");
+        }
+
+        let cooldown = env.storage()
+            .persistent()
+            .get::<_, StakingConfig>(&DataKey::StakingConfig)
+            .map(|config| config.cooldown)
+            .unwrap_or(0);
+
+        stake.amount -= amount;
+        stake.unstaking += amount;
+        stake.unstake_available_at = now + cooldown;
+        env.storage().persistent().set(&DataKey::Stake(user.clone()), &stake);
+
+        log!(&env, "Unstake requested: {} tokens by {}", amount, user);
+    }
+
+    /// Withdraw tokens whose unstaking cooldown has passed
+    pub fn withdraw_unstaked(env: Env, user: Address) -> i128 {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::All);
+        user.require_auth();
+
+        let mut stake = Self::get_stake(env.clone(), user.clone());
+        let amount = stake.unstaking;
+        if amount <= 0 {
+            panic!("Nothing to withdraw");
+        }
+
+        if env.ledger().timestamp() < stake.unstake_available_at {
+            panic!("Unstake cooldown has not passed");
+        }
+
+        stake.unstaking = 0;
+        env.storage().persistent().set(&DataKey::Stake(user.clone()), &stake);
+        Self::escrow_out(&env, &user, amount);
+
+        log!(&env, "Unstaked {} tokens withdrawn by {}", amount, user);
+        amount
+    }
+
+    /// Get a user's stake
+    pub fn get_stake(env: Env, user: Address) -> StakeInfo {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Stake(user))
+            .unwrap_or(StakeInfo {
+                amount: 0,
+                locked_until: 0,
+                unstaking: 0,
+                unstake_available_at: 0,
+            })
+    }
+
+    /// Get a user's perk level from their active stake (0 = no perks)
+    pub fn perk_level(env: Env, user: Address) -> u32 {
+        let staked = Self::get_stake(env.clone(), user).amount;
+        let config: Option<StakingConfig> = env.storage().persistent().get(&DataKey::StakingConfig);
+
+        let mut level: u32 = 0;
+        if let Some(config) = config {
+            for threshold in config.level_thresholds.iter() {
+                if staked < threshold {
+                    break;
+                }
+                level += 1;
+            }
+        }
+        level
+    }
+
+    /// Get token balance
+    pub fn balance(env: Env, user: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Balance(user))
+            .unwrap_or(0)
+    }
+
+    /// Get a user's balance as of the end of ledger `ledger`
+    pub fn balance_at(env: Env, user: Address, ledger: u32) -> i128 {
+        let value = Self::checkpoint_at(
+            &env,
+            DataKey::BalanceCheckpointCount(user.clone()),
+            |index| DataKey::BalanceCheckpoint(user.clone(), index),
+            ledger,
+        );
+
+        match Self::legacy_balance(&env, &user) {
+            Some(legacy) if ledger >= legacy.ledger => legacy.value,
+            _ => value,
+        }
+    }
+
+    /// Get the total supply as of the end of ledger `ledger`
+    pub fn total_supply_at(env: Env, ledger: u32) -> i128 {
+        Self::checkpoint_at(&env, DataKey::SupplyCheckpointCount, DataKey::SupplyCheckpoint, ledger)
+    }
+
+    /// Get token info
+    pub fn token_info(env: Env) -> RewardToken {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TokenInfo)
+            .expect("Token not initialized")
+    }
+
+    /// Transfer tokens between users
+    pub fn transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Transfers);
+        from.require_auth();
+
+        if amount <= 0 {
+            panic!("Transfer amount must be positive");
+        }
+
+        Self::move_balance(&env, &from, &to, amount);
+
+        log!(&env, "Transferred {} tokens from {} to {}", amount, from, to);
+    }
+
+    /// Check if user has claimed reward for event
+    pub fn has_claimed_reward(env: Env, user: Address, event_id: u64) -> bool {
+        let claim_key = DataKey::ClaimedRewards(user, event_id);
+        env.storage().persistent().has(&claim_key)
+    }
+
+    /// Get the base reward per attendee of an event's pool (0 without a pool)
+    pub fn get_event_reward(env: Env, event_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get::<_, RewardPool>(&DataKey::RewardPool(event_id))
+            .map(|pool| pool.per_attendee)
+            .unwrap_or(0)
+    }
+
+    /// Get claimed count, unclaimed liability and claim deadline for an event's rewards
+    pub fn get_reward_claim_stats(env: Env, event_id: u64) -> RewardClaimStats {
+        let unclaimed = env.storage()
+            .persistent()
+            .get::<_, RewardPool>(&DataKey::RewardPool(event_id))
+            .map(|pool| pool.remaining)
+            .unwrap_or(0);
+
+        RewardClaimStats {
+            claimed_count: env.storage().persistent().get(&DataKey::ClaimCount(event_id)).unwrap_or(0),
+            claimed_total: env.storage().persistent().get(&DataKey::ClaimedTotal(event_id)).unwrap_or(0),
+            unclaimed,
+            claim_deadline: env.storage().persistent().get(&DataKey::ClaimDeadline(event_id)).unwrap_or(0),
+        }
+    }
+
+    /// Mint additional tokens (admin only)
+    pub fn mint(env: Env, admin: Address, amount: i128) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Mints);
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can mint tokens");
+        }
+
+        if amount <= 0 {
+            panic!("Mint amount must be positive");
+        }
+
+        let admin_balance: i128 = env.storage()
+            .persistent()
+            .get(&DataKey::Balance(admin.clone()))
+            .unwrap_or(0);
+        
+        let new_admin_balance = admin_balance + amount;
+
+        // Update total supply
+        let mut token_info: RewardToken = env.storage()
+            .persistent()
+            .get(&DataKey::TokenInfo)
+            .expect("Token not initialized");
+        
+        if token_info.total_supply + amount > token_info.max_supply {
+            panic!("Mint would exceed max supply");
+        }
+
+        token_info.total_supply += amount;
+
+        Self::write_balance(&env, &admin, new_admin_balance);
+        Self::write_token_info(&env, &token_info);
+
+        log!(&env, "Minted {} tokens to admin", amount);
+    }
+
+    /// Destroy tokens from the caller's balance
+    pub fn burn(env: Env, from: Address, amount: i128) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Transfers);
+        from.require_auth();
+
+        Self::burn_balance(&env, &from, amount);
+
+        log!(&env, "Burned {} tokens from {}", amount, from);
+    }
+
+    /// Destroy tokens from `from` using an allowance granted to `spender`
+    pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Transfers);
+        spender.require_auth();
+
+        let allowance_key = DataKey::Allowance(from.clone(), spender.clone());
+        let allowance: i128 = env.storage().persistent().get(&allowance_key).unwrap_or(0);
+        if allowance < amount {
+            panic!("Insufficient allowance");
+        }
+
+        env.storage().persistent().set(&allowance_key, &(allowance - amount));
+        Self::burn_balance(&env, &from, amount);
+
+        log!(&env, "Burned {} tokens from {} by {}", amount, from, spender);
+    }
+
+    /// Allow `spender` to burn up to `amount` of the caller's tokens
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128) {
+        upgradeable::require_migrated(&env, STORAGE_VERSION);
+        pausable::when_not_paused(&env, PauseScope::Transfers);
+        from.require_auth();
+
+        if amount < 0 {
+            panic!("Allowance must not be negative");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Allowance(from.clone(), spender.clone()), &amount);
+
+        log!(&env, "Approved {} tokens from {} for {}", amount, from, spender);
+    }
+
+    /// Get the amount `spender` may still burn from `from`
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Allowance(from, spender))
+            .unwrap_or(0)
+    }
+
+    /// Get the supply held outside the contract (reward pools, unvested rewards,
+    /// stakes) and outside the admin treasury. Panics if the supply exceeds its cap
+    /// or the contract holds less than it owes.
+    pub fn circulating_supply(env: Env) -> i128 {
+        let token_info = Self::token_info(env.clone());
+        let contract_held = Self::balance(env.clone(), env.current_contract_address());
+        let treasury = match ownership::get_owner(&env) {
+            Some(admin) => Self::balance(env.clone(), admin),
+            None => 0,
+        };
+
+        // Direct transfers to the contract may leave it holding more than it owes
+        if token_info.total_supply > token_info.max_supply || contract_held < Self::escrowed(&env) {
+            panic!("Supply invariant violated");
+        }
+        token_info.total_supply - contract_held - treasury
+    }
+
+    /// Get current admin
+    pub fn get_admin(env: Env) -> Address {
+        ownership::get_owner(&env).expect("Admin not set")
+    }
+
+    /// Nominate a new admin; takes effect once they accept (admin only)
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address) {
+        ownership::propose(&env, &admin, &new_admin);
+    }
+
+    /// Accept a pending admin nomination
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        ownership::accept(&env, &new_admin);
+    }
+
+    /// Permanently give up the admin role (admin only)
+    pub fn renounce_admin(env: Env, admin: Address) {
+        ownership::renounce(&env, &admin);
+    }
+
+    /// Get the admin nominated but not yet accepted, if any
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        ownership::get_pending_owner(&env)
+    }
+
+    /// Appoint the pause guardian (admin only)
+    pub fn set_pause_guardian(env: Env, admin: Address, guardian: Address) {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can set the pause guardian");
+        }
+
+        pausable::set_guardian(&env, &guardian);
+    }
+
+    /// Pause a scope of entry points, optionally until `expires_at` (guardian only)
+    pub fn pause(env: Env, guardian: Address, scope: PauseScope, expires_at: Option<u64>) {
+        pausable::pause(&env, &guardian, scope, expires_at);
+    }
+
+    /// Lift a pause (guardian only)
+    pub fn unpause(env: Env, guardian: Address, scope: PauseScope) {
+        pausable::unpause(&env, &guardian, scope);
+    }
+
+    /// Check whether a scope is currently paused
+    pub fn is_paused(env: Env, scope: PauseScope) -> bool {
+        pausable::is_paused(&env, scope)
+    }
+
+    /// Get the pause guardian, if any
+    pub fn get_pause_guardian(env: Env) -> Option<Address> {
+        pausable::get_guardian(&env)
+    }
+
+    /// Replace the contract code, keeping all storage (admin only)
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can upgrade the contract");
+        }
+
+        upgradeable::upgrade(&env, new_wasm_hash);
+    }
+
+    /// Migrate up to `limit` storage entries towards the current layout (admin only)
+    /// Returns the storage version afterwards; call again until it equals `STORAGE_VERSION`.
+    /// Storage cannot be listed, so `last_event_id` bounds the event IDs searched
+    /// for reward pools; pass the same value on every call.
+    pub fn migrate(env: Env, admin: Address, limit: u32, last_event_id: u64) -> u32 {
+        admin.require_auth();
+
+        if !ownership::is_owner(&env, &admin) {
+            panic!("Only admin can migrate storage");
+        }
+
+        env.storage().persistent().set(&DataKey::MigrationLastEvent, &last_event_id);
+        let version = upgradeable::migrate(&env, STORAGE_VERSION, limit, Self::migrate_step);
+        if version == STORAGE_VERSION {
+            env.storage().persistent().remove(&DataKey::MigrationLastEvent);
+        }
+        version
+    }
+
+    /// Get the storage layout version
+    pub fn version(env: Env) -> u32 {
+        upgradeable::get_version(&env)
+    }
+}
+
+impl TokenRewards {
+    /// Migration step `from_version -> from_version + 1`; see `upgradeable::MigrationStep`
+    fn migrate_step(env: &Env, from_version: u32, cursor: u32, limit: u32) -> Option<u32> {
+        match from_version {
+            1 => Self::migrate_v1(env, cursor, limit),
+            _ => upgradeable::no_steps(env, from_version, cursor, limit),
+        }
+    }
+
+    /// Version 1 -> 2: balance and supply checkpoints, custody accounting, the
+    /// staking minimum lock, and per-attendee rewards, claim deadlines and funder
+    /// contributions of reward pools.
+    ///
+    /// Cursor 0 carries the unindexed holders' balances over as of this ledger,
+    /// which `legacy_balance` checkpoints lazily, and counts everything the
+    /// contract holds as owed. Cursor `n` then migrates event `n`'s pool, up to
+    /// the `last_event_id` given to `migrate`.
+    fn migrate_v1(env: &Env, cursor: u32, limit: u32) -> Option<u32> {
+        let storage = env.storage().persistent();
+
+        if cursor == 0 {
+            storage.set(&DataKey::BalancesMigratedAt, &env.ledger().sequence());
+            if !storage.has(&DataKey::SupplyCheckpointCount) {
+                let token_info: RewardToken = storage
+                    .get(&DataKey::TokenInfo)
+                    .expect("Token not initialized");
+                Self::write_token_info(env, &token_info);
+            }
+
+            let held: i128 = storage
+                .get(&DataKey::Balance(env.current_contract_address()))
+                .unwrap_or(0);
+            storage.set(&DataKey::Escrowed, &held);
+
+            if let Some(old) = storage.get::<_, StakingConfigV1>(&DataKey::StakingConfig) {
+                let config = StakingConfig {
+                    level_thresholds: old.level_thresholds,
+                    min_lock: 0,
+                    cooldown: old.cooldown,
+                };
+                storage.set(&DataKey::StakingConfig, &config);
+            }
+            return Some(1);
+        }
+
+        let last_event_id: u64 = storage.get(&DataKey::MigrationLastEvent).unwrap_or(0);
+        let last = last_event_id.min(cursor as u64 + limit as u64 - 1);
+        for event_id in (cursor as u64)..=last {
+            let legacy_key = DataKeyV1::EventRewards(event_id);
+            let legacy_reward: Option<i128> = storage.get(&legacy_key);
+            storage.remove(&legacy_key);
+
+            let mut pool: RewardPool = match storage.get(&DataKey::RewardPool(event_id)) {
+                Some(pool) => pool,
+                None => continue,
+            };
+            if let Some(per_attendee) = legacy_reward {
+                pool.per_attendee = per_attendee;
+                storage.set(&DataKey::RewardPool(event_id), &pool);
+            }
+
+            // Version 1 claims never closed; the pool deadline now closes them
+            if !storage.has(&DataKey::ClaimDeadline(event_id)) {
+                storage.set(&DataKey::ClaimDeadline(event_id), &pool.deadline);
+            }
+            // Only the opener could fund a version 1 pool
+            if !storage.has(&DataKey::PoolUnreclaimed(event_id)) {
+                Self::add_contribution(env, event_id, &pool.funder, pool.budget);
+            }
+        }
+
+        if last >= last_event_id {
+            None
+        } else {
+            Some(u32::try_from(last + 1).expect("Migration cursor overflow"))
+        }
+    }
+
+    /// Pay a claimed reward out of the pool: straight to the balance, or into a
+    /// vesting grant if the event has a schedule
+    fn pay_reward(env: &Env, event_id: u64, to: &Address, amount: i128) {
+        let schedule: Option<VestingSchedule> = env.storage()
+            .persistent()
+            .get(&DataKey::VestingSchedule(event_id));
+
+        match schedule {
+            None => Self::escrow_out(env, to, amount),
+            Some(schedule) => {
+                // Tokens stay in the contract until withdrawn
+                let mut grants: Vec<VestingGrant> = env.storage()
+                    .persistent()
+                    .get(&DataKey::VestingGrants(to.clone()))
+                    .unwrap_or(Vec::new(env));
+                grants.push_back(VestingGrant {
+                    event_id,
+                    total: amount,
+                    withdrawn: 0,
+                    schedule,
+                    revoked: false,
+                });
+                env.storage().persistent().set(&DataKey::VestingGrants(to.clone()), &grants);
+            }
+        }
+    }
+
+    /// Whether `account` may open an event's reward pool: the admin, or the event's
+    /// organizer according to the attendance recorder (EventManager)
+    fn can_open_pool(env: &Env, account: &Address, event_id: u64) -> bool {
+        if ownership::is_owner(env, account) {
+            return true;
+        }
+
+        match env.storage().persistent().get::<_, Address>(&DataKey::AttendanceRecorder) {
+            Some(recorder) => {
+                let organizer: Address = env.invoke_contract(
+                    &recorder,
+                    &Symbol::new(env, "get_event_organizer"),
+                    (event_id,).into_val(env),
+                );
+                organizer == *account
+            }
+            None => false,
+        }
+    }
+
+    /// Record a deposit into an event's pool as owed back to `funder` pro rata
+    fn add_contribution(env: &Env, event_id: u64, funder: &Address, amount: i128) {
+        let key = DataKey::PoolContribution(event_id, funder.clone());
+        let contribution: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(contribution + amount));
+
+        let unreclaimed: i128 = env.storage()
+            .persistent()
+            .get(&DataKey::PoolUnreclaimed(event_id))
+            .unwrap_or(0);
+        env.storage().persistent().set(&DataKey::PoolUnreclaimed(event_id), &(unreclaimed + amount));
+    }
+
+    /// Whether the event's claim deadline has passed
+    fn claims_closed(env: &Env, event_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .get::<_, u64>(&DataKey::ClaimDeadline(event_id))
+            .is_some_and(|deadline| env.ledger().timestamp() > deadline)
+    }
+
+    fn require_claim_window(env: &Env, event_id: u64) {
+        if Self::claims_closed(env, event_id) {
+            panic!("Reward claim window has closed");
+        }
+    }
+
+    /// Count a paid claim towards the event's claim stats
+    fn record_claim(env: &Env, event_id: u64, amount: i128) {
+        let count: u32 = env.storage().persistent().get(&DataKey::ClaimCount(event_id)).unwrap_or(0);
+        let total: i128 = env.storage().persistent().get(&DataKey::ClaimedTotal(event_id)).unwrap_or(0);
+        env.storage().persistent().set(&DataKey::ClaimCount(event_id), &(count + 1));
+        env.storage().persistent().set(&DataKey::ClaimedTotal(event_id), &(total + amount));
+    }
+
+    /// Base reward scaled by the user's loyalty multiplier, plus any streak bonus
+    /// earned at this event
+    fn reward_for(env: &Env, user: &Address, event_id: u64, base: i128) -> i128 {
+        let status = Self::get_loyalty_status(env.clone(), user.clone());
+        let reward = base * status.multiplier_bps as i128 / BPS_DENOMINATOR as i128;
+
+        let streak: u32 = env.storage()
+            .persistent()
+            .get(&DataKey::Attended(user.clone(), event_id))
+            .unwrap_or(0);
+        let position: Option<SeriesPosition> = env.storage()
+            .persistent()
+            .get(&DataKey::EventSeries(event_id));
+
+        let bonus_bps = match position {
+            Some(position) if streak > 1 => env.storage()
+                .persistent()
+                .get::<_, StreakBonus>(&DataKey::StreakBonus(position.series_id))
+                .map(|bonus| (streak - 1).saturating_mul(bonus.step_bps).min(bonus.max_bps))
+                .unwrap_or(0),
+            _ => 0,
+        };
+
+        reward + base * bonus_bps as i128 / BPS_DENOMINATOR as i128
+    }
+
+    /// Update the user's streak for the event's series and return it (0 if the
+    /// event is not in a series). Missing a sequence number resets the streak.
+    fn advance_streak(env: &Env, user: &Address, event_id: u64) -> u32 {
+        let position: SeriesPosition = match env.storage()
+            .persistent()
+            .get(&DataKey::EventSeries(event_id))
+        {
+            Some(position) => position,
+            None => return 0,
+        };
+
+        let key = DataKey::Streak(user.clone(), position.series_id);
+        let mut streak = Self::get_streak(env.clone(), user.clone(), position.series_id);
+
+        if streak.count > 0 && position.sequence <= streak.last_sequence {
+            // Late check-in for an earlier event does not change the streak
+            return 1;
+        }
+
+        if streak.count > 0 && position.sequence == streak.last_sequence + 1 {
+            streak.count += 1;
+        } else {
+            streak.count = 1;
+        }
+        streak.last_sequence = position.sequence;
+        env.storage().persistent().set(&key, &streak);
+
+        streak.count
+    }
+
+    /// Amount of a grant vested at `now`: nothing before the cliff, then linear
+    fn vested_amount(grant: &VestingGrant, now: u64) -> i128 {
+        if grant.revoked {
+            return grant.total;
+        }
+
+        let schedule = &grant.schedule;
+        let elapsed = now.saturating_sub(schedule.start);
+        if elapsed < schedule.cliff {
+            0
+        } else if elapsed >= schedule.duration {
+            grant.total
+        } else {
+            grant.total * elapsed as i128 / schedule.duration as i128
+        }
+    }
+
+    /// Remove `amount` from a balance and from the total supply
+    fn burn_balance(env: &Env, from: &Address, amount: i128) {
+        if amount <= 0 {
+            panic!("Burn amount must be positive");
+        }
+
+        let balance: i128 = env.storage()
+            .persistent()
+            .get(&DataKey::Balance(from.clone()))
+            .unwrap_or(0);
+
+        if balance < amount {
+            panic!("Insufficient balance");
+        }
+
+        let mut token_info: RewardToken = env.storage()
+            .persistent()
+            .get(&DataKey::TokenInfo)
+            .expect("Token not initialized");
+        token_info.total_supply -= amount;
+
+        Self::write_balance(env, from, balance - amount);
+        Self::write_token_info(env, &token_info);
+    }
+
+    /// Take `amount` from `from` into the contract's custody
+    fn escrow_in(env: &Env, from: &Address, amount: i128) {
+        Self::move_balance(env, from, &env.current_contract_address(), amount);
+        env.storage().persistent().set(&DataKey::Escrowed, &(Self::escrowed(env) + amount));
+    }
+
+    /// Release `amount` from the contract's custody to `to`
+    fn escrow_out(env: &Env, to: &Address, amount: i128) {
+        Self::move_balance(env, &env.current_contract_address(), to, amount);
+        env.storage().persistent().set(&DataKey::Escrowed, &(Self::escrowed(env) - amount));
+    }
+
+    /// Tokens the contract owes to pools, vesting grants, stakes, drops and referrals
+    fn escrowed(env: &Env) -> i128 {
+        env.storage().persistent().get(&DataKey::Escrowed).unwrap_or(0)
+    }
+
+    /// Move `amount` from one balance to another, panicking if `from` is short
+    fn move_balance(env: &Env, from: &Address, to: &Address, amount: i128) {
+        let from_balance: i128 = env.storage()
+            .persistent()
+            .get(&DataKey::Balance(from.clone()))
+            .unwrap_or(0);
+
+        if from_balance < amount {
+            panic!("Insufficient balance");
+        }
+
+        if from == to {
+            return;
+        }
+
+        let to_balance: i128 = env.storage()
+            .persistent()
+            .get(&DataKey::Balance(to.clone()))
+            .unwrap_or(0);
+
+        Self::write_balance(env, from, from_balance - amount);
+        Self::write_balance(env, to, to_balance + amount);
+    }
+
+    /// Store a balance; every balance change goes through here so it is checkpointed
+    fn write_balance(env: &Env, user: &Address, balance: i128) {
+        if let Some(legacy) = Self::legacy_balance(env, user) {
+            env.storage().persistent().set(&DataKey::BalanceCheckpoint(user.clone(), 0), &legacy);
+            env.storage().persistent().set(&DataKey::BalanceCheckpointCount(user.clone()), &1u32);
+        }
+
+        env.storage().persistent().set(&DataKey::Balance(user.clone()), &balance);
+        Self::push_checkpoint(
+            env,
+            DataKey::BalanceCheckpointCount(user.clone()),
+            |index| DataKey::BalanceCheckpoint(user.clone(), index),
+            balance,
+        );
+    }
+
+    /// Store token info, checkpointing the total supply
+    fn write_token_info(env: &Env, token_info: &RewardToken) {
+        env.storage().persistent().set(&DataKey::TokenInfo, token_info);
+        Self::push_checkpoint(
+            env,
+            DataKey::SupplyCheckpointCount,
+            DataKey::SupplyCheckpoint,
+            token_info.total_supply,
+        );
+    }
+
+    /// Balance carried over from storage version 1 and not changed since, as a
+    /// checkpoint at the ledger it was migrated in
+    fn legacy_balance(env: &Env, user: &Address) -> Option<Checkpoint> {
+        if env.storage().persistent().has(&DataKey::BalanceCheckpointCount(user.clone())) {
+            return None;
+        }
+
+        let ledger: u32 = env.storage().persistent().get(&DataKey::BalancesMigratedAt)?;
+        let value: i128 = env.storage()
+            .persistent()
+            .get(&DataKey::Balance(user.clone()))
+            .unwrap_or(0);
+        (value > 0).then_some(Checkpoint { ledger, value })
+    }
+
+    /// Record `value` for the current ledger, replacing an earlier write in the same ledger
+    fn push_checkpoint(env: &Env, count_key: DataKey, key_at: impl Fn(u32) -> DataKey, value: i128) {
+        let ledger = env.ledger().sequence();
+        let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+
+        let same_ledger = count > 0
+            && env.storage()
+                .persistent()
+                .get::<_, Checkpoint>(&key_at(count - 1))
+                .is_some_and(|last| last.ledger == ledger);
+
+        let index = if same_ledger { count - 1 } else { count };
+        env.storage().persistent().set(&key_at(index), &Checkpoint { ledger, value });
+        env.storage().persistent().set(&count_key, &(index + 1));
+    }
+
+    /// Value of the last checkpoint at or before `ledger` (0 if none), by binary search
+    fn checkpoint_at(env: &Env, count_key: DataKey, key_at: impl Fn(u32) -> DataKey, ledger: u32) -> i128 {
+        if ledger > env.ledger().sequence() {
+            panic!("Ledger is in the future");
+        }
+
+        // Find the number of checkpoints with `checkpoint.ledger <= ledger`
+        let (mut low, mut high): (u32, u32) = (0, env.storage().persistent().get(&count_key).unwrap_or(0));
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let checkpoint: Checkpoint = env.storage().persistent().get(&key_at(mid)).unwrap();
+            if checkpoint.ledger <= ledger {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        if low == 0 {
+            return 0;
+        }
+        env.storage().persistent().get::<_, Checkpoint>(&key_at(low - 1)).unwrap().value
     }
 }
@@ -0,0 +1,87 @@
+//! Shared in-place upgrades and storage-layout versioning.
+//!
+//! Each contract stores the version of its storage layout at `init`. After an
+//! `upgrade` to code with a newer layout, the owner calls `migrate` repeatedly;
+//! every call rewrites at most `limit` entries so a migration never exceeds the
+//! per-transaction resource limits.
+//!
+//! A contract that changes its layout bumps its `STORAGE_VERSION` and adds a
+//! `MigrationStep` matching on `from_version`, with `no_steps` for versions it
+//! has no step from; one still on its first layout passes `no_steps` directly.
+//!
+//! Entry points that change state call `require_migrated` first, so nothing
+//! writes the new layout over entries a migration has yet to rewrite. Admin
+//! handover, pausing, `upgrade` and `migrate` itself stay available.
+use soroban_sdk::{contracttype, BytesN, Env, Symbol};
+
+#[derive(Clone)]
+#[contracttype]
+pub enum UpgradeKey {
+    StorageVersion,
+    MigrationCursor, // position within the current version step
+}
+
+/// Migrates up to `limit` entries of the step `from_version -> from_version + 1`,
+/// starting at `cursor`. Returns the next cursor, or `None` once the step is done.
+pub type MigrationStep = fn(env: &Env, from_version: u32, cursor: u32, limit: u32) -> Option<u32>;
+
+/// Step for a contract still on its first storage layout
+pub fn no_steps(_env: &Env, _from_version: u32, _cursor: u32, _limit: u32) -> Option<u32> {
+    panic!("No migration from this storage version")
+}
+
+pub fn get_version(env: &Env) -> u32 {
+    env.storage().instance().get(&UpgradeKey::StorageVersion).unwrap_or(0)
+}
+
+pub fn set_version(env: &Env, version: u32) {
+    env.storage().instance().set(&UpgradeKey::StorageVersion, &version);
+}
+
+/// Panic unless storage is at `target`, i.e. no migration is pending or under way
+pub fn require_migrated(env: &Env, target: u32) {
+    if get_version(env) != target {
+        panic!("Storage migration in progress");
+    }
+}
+
+/// Replace the contract code; storage is kept as is.
+pub fn upgrade(env: &Env, new_wasm_hash: BytesN<32>) {
+    env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+    env.events().publish(
+        (Symbol::new(env, "upgrade"), Symbol::new(env, "wasm")),
+        new_wasm_hash,
+    );
+}
+
+/// Run one batch of the migration towards `target` and return the storage
+/// version afterwards.
+pub fn migrate(env: &Env, target: u32, limit: u32, step: MigrationStep) -> u32 {
+    let version = get_version(env);
+    if version >= target {
+        panic!("Storage is already at the current version");
+    }
+
+    if limit == 0 {
+        panic!("Migration batch limit must be positive");
+    }
+
+    let cursor: u32 = env.storage().instance().get(&UpgradeKey::MigrationCursor).unwrap_or(0);
+    match step(env, version, cursor, limit) {
+        Some(next) => {
+            env.storage().instance().set(&UpgradeKey::MigrationCursor, &next);
+            version
+        }
+        None => {
+            env.storage().instance().remove(&UpgradeKey::MigrationCursor);
+            set_version(env, version + 1);
+
+            env.events().publish(
+                (Symbol::new(env, "upgrade"), Symbol::new(env, "migrated")),
+                version + 1,
+            );
+            version + 1
+        }
+    }
+}